@@ -1,44 +1,71 @@
 use std::{
     env,
-    fs::File,
+    fs,
     io::{
 		self,
 		BufRead,
-		BufReader,
 		stdin,
 	},
+    process,
+	thread,
 };
-use rust_apl::eval::eval::{
-	Evaluator,
-	Printable,
+use rust_apl::{
+	error::AplError,
+	eval::{
+		environment::EVALUATOR_STACK_SIZE,
+		eval::{
+			Evaluator,
+			Printable,
+		},
+	},
 };
 
+//Evaluation runs on a thread of its own, as deep recursion needs more stack than the main
+//thread has
 fn main() -> io::Result<()> {
-	println!("Rust-APL version 0.0.1");
+	thread::Builder::new()
+		.stack_size(EVALUATOR_STACK_SIZE)
+		.spawn(session)?
+		.join()
+		.unwrap_or_else(|_| process::exit(101))
+}
+
+fn session() -> io::Result<()> {
 	let args: Vec<String> = env::args().collect();
 
 	// Decide where to read from: file if an argument is provided, otherwise stdin.
 	if let Some(path) = args.get(1) {
-		let file = File::open(path)?;
-		run(BufReader::new(file))
+		let source = fs::read_to_string(path)?;
+		run_script(source)
 	} else {
+		println!("Rust-APL version 0.0.1");
 		let stdin = stdin();
 		run(stdin.lock())
 	}
 }
 
+//Runs a whole file as one program, stopping with a failure status at the first error
+fn run_script(source: String) -> io::Result<()> {
+	let mut eval = Evaluator::with_stack_size(EVALUATOR_STACK_SIZE);
+	if let Err(error) = eval.run_program(source.clone(), |result| println!("{}", result.to_string())) {
+		report(&error, &source);
+		process::exit(1);
+	}
+	Ok(())
+}
+
+//Reads statements a line at a time, reporting errors and carrying on
 fn run<R: BufRead>(mut reader: R) -> io::Result<()> {
 	let mut line = String::new();
+	let mut eval = Evaluator::with_stack_size(EVALUATOR_STACK_SIZE);
 
 	loop {
 		line.clear();
 		match reader.read_line(&mut line) {
 			Ok(0) => break, // EOF
 			Ok(_) => {
-				let mut eval = Evaluator::new(line.clone());
-				match eval.eval() {
-					Ok(result) => println!("{}", result.to_string()),
-					Err(msg) => eprintln!("Error: {}", msg),
+				if let Err(error) = eval.run_program(line.clone(), |result| println!("{}", result.to_string())) {
+					report(&error, &line);
 				}
 			}
 			Err(err) => {
@@ -50,3 +77,13 @@ fn run<R: BufRead>(mut reader: R) -> io::Result<()> {
 
 	Ok(())
 }
+
+//Prints the error, then the line it came from with a caret under the failing token
+fn report(error: &AplError, source: &str) {
+	eprintln!("{}", error);
+	if let Some(span) = error.span()
+		&& let Some(line) = span.row.checked_sub(1).and_then(|row| source.lines().nth(row)) {
+		eprintln!("{}", line);
+		eprintln!("{}^", " ".repeat(span.col.saturating_sub(1)));
+	}
+}
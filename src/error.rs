@@ -0,0 +1,139 @@
+use std::{
+	error::Error,
+	fmt,
+};
+use crate::tokenizer::Token;
+
+//The row and column of the token an error was raised at
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+	pub row: usize,
+	pub col: usize
+}
+
+//The APL2 error classes. Each carries an optional detail message and the position of the
+//primitive or token that failed, once one is known.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AplError {
+	Axis(String, Option<Span>),
+	Domain(String, Option<Span>),
+	Index(String, Option<Span>),
+	Length(String, Option<Span>),
+	Limit(String, Option<Span>),
+	Nonce(String, Option<Span>),
+	Rank(String, Option<Span>),
+	Syntax(String, Option<Span>),
+	Valence(String, Option<Span>),
+	Value(String, Option<Span>),
+}
+
+impl AplError {
+	pub fn axis(message: impl Into<String>) -> AplError {
+		AplError::Axis(message.into(), None)
+	}
+
+	pub fn domain(message: impl Into<String>) -> AplError {
+		AplError::Domain(message.into(), None)
+	}
+
+	pub fn index(message: impl Into<String>) -> AplError {
+		AplError::Index(message.into(), None)
+	}
+
+	pub fn length(message: impl Into<String>) -> AplError {
+		AplError::Length(message.into(), None)
+	}
+
+	pub fn limit(message: impl Into<String>) -> AplError {
+		AplError::Limit(message.into(), None)
+	}
+
+	pub fn nonce(message: impl Into<String>) -> AplError {
+		AplError::Nonce(message.into(), None)
+	}
+
+	pub fn rank(message: impl Into<String>) -> AplError {
+		AplError::Rank(message.into(), None)
+	}
+
+	pub fn syntax(message: impl Into<String>) -> AplError {
+		AplError::Syntax(message.into(), None)
+	}
+
+	pub fn valence(message: impl Into<String>) -> AplError {
+		AplError::Valence(message.into(), None)
+	}
+
+	pub fn value(message: impl Into<String>) -> AplError {
+		AplError::Value(message.into(), None)
+	}
+
+	fn parts(&self) -> (&str, &String, &Option<Span>) {
+		match self {
+			AplError::Axis(message, span) => ("AXIS ERROR", message, span),
+			AplError::Domain(message, span) => ("DOMAIN ERROR", message, span),
+			AplError::Index(message, span) => ("INDEX ERROR", message, span),
+			AplError::Length(message, span) => ("LENGTH ERROR", message, span),
+			AplError::Limit(message, span) => ("LIMIT ERROR", message, span),
+			AplError::Nonce(message, span) => ("NONCE ERROR", message, span),
+			AplError::Rank(message, span) => ("RANK ERROR", message, span),
+			AplError::Syntax(message, span) => ("SYNTAX ERROR", message, span),
+			AplError::Valence(message, span) => ("VALENCE ERROR", message, span),
+			AplError::Value(message, span) => ("VALUE ERROR", message, span),
+		}
+	}
+
+	fn span_mut(&mut self) -> &mut Option<Span> {
+		match self {
+			AplError::Axis(_, span) | AplError::Domain(_, span) | AplError::Index(_, span) |
+			AplError::Length(_, span) | AplError::Limit(_, span) | AplError::Nonce(_, span) |
+			AplError::Rank(_, span) | AplError::Syntax(_, span) | AplError::Valence(_, span) |
+			AplError::Value(_, span) => span
+		}
+	}
+
+	pub fn class(&self) -> &str {
+		self.parts().0
+	}
+
+	pub fn message(&self) -> &str {
+		self.parts().1
+	}
+
+	pub fn span(&self) -> Option<Span> {
+		*self.parts().2
+	}
+
+	//Points the error at a token, unless something nearer the failure already has
+	pub fn at(mut self, token: &Token) -> AplError {
+		if self.span().is_none() {
+			self.relocate(token);
+		}
+		self
+	}
+
+	//Points the error at a position in the source, for errors raised before there is a token
+	pub fn at_position(mut self, row: usize, col: usize) -> AplError {
+		*self.span_mut() = Some(Span { row, col });
+		self
+	}
+
+	//Points the error at a token, even if it was raised somewhere else
+	pub fn relocate(&mut self, token: &Token) {
+		if let Some(token_data) = token.token_data() {
+			*self.span_mut() = Some(Span { row: token_data.row, col: token_data.col });
+		}
+	}
+}
+
+impl fmt::Display for AplError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.message().is_empty() {
+			write!(f, "{}", self.class())
+		} else {
+			write!(f, "{}: {}", self.class(), self.message())
+		}
+	}
+}
+
+impl Error for AplError {}
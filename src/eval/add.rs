@@ -3,20 +3,18 @@ use num::complex::{
 	Complex64,
 };
 use crate::{
-	nodes::Node,
+	error::AplError,
 	eval::{
-		eval::{
-			Value,
-			eval_dyadic,
-		},
+		eval::Value,
 		array_helpers::{
 			simple_dyadic_array,
 			dual_dyadic_array,
+			integer_or_float,
 		},
 	},
 };
 
-fn add_float(f: f64, other:&Value) -> Result<Box<Value>, String> {
+fn add_float(f: f64, other:&Value) -> Result<Box<Value>, AplError> {
 	match *other {
 		Value::AplFloat(val) => {
 			Ok(Box::new(Value::AplFloat(f + val)))
@@ -27,30 +25,36 @@ fn add_float(f: f64, other:&Value) -> Result<Box<Value>, String> {
 		Value::AplComplex(_val) => {
 			add_complex(&Complex::new(f, 0.0), other)
 		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
 		Value::AplArray(_, _, _) => {
 			simple_dyadic_array(add_float, f, other)
 		}
 	}
 }
 
-fn add_integer(i: isize, other:&Value) -> Result<Box<Value>, String> {
+fn add_integer(i: isize, other:&Value) -> Result<Box<Value>, AplError> {
 	match *other {
 		Value::AplFloat(_val) => {
 			add_float(i as f64, other)
 		},
 		Value::AplInteger(val) => {
-			Ok(Box::new(Value::AplInteger(i + val)))
+			Ok(integer_or_float(i.checked_add(val), || i as f64 + val as f64))
 		},
 		Value::AplComplex(_val) => {
 			add_complex(&Complex::new(i as f64, 0.0), other)
 		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
 		Value::AplArray(_, _, _) => {
 			simple_dyadic_array(add_integer, i, other)
 		}
 	}
 }
 
-fn add_complex(c: &Complex64, other: &Value) -> Result<Box<Value>, String> {
+fn add_complex(c: &Complex64, other: &Value) -> Result<Box<Value>, AplError> {
 	match *other {
 		Value::AplFloat(f) => {
 			add_complex(c, &Value::AplComplex(Complex::new(f, 0.0)))
@@ -61,13 +65,16 @@ fn add_complex(c: &Complex64, other: &Value) -> Result<Box<Value>, String> {
 		Value::AplComplex(other_c) => {
 			Ok(Box::new(Value::AplComplex(c + other_c)))
 		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
 		Value::AplArray(_, _, _) => {
 			simple_dyadic_array(add_complex, c, other)
 		}
 	}
 }
 
-fn add_array(array: &Value, other: &Value) -> Result<Box<Value>, String> {
+fn add_array(array: &Value, other: &Value) -> Result<Box<Value>, AplError> {
 	match *other {
 		Value::AplFloat(val) => {
 			simple_dyadic_array(add_float, val, array)
@@ -78,13 +85,16 @@ fn add_array(array: &Value, other: &Value) -> Result<Box<Value>, String> {
 		Value::AplComplex(val) => {
 			simple_dyadic_array(add_complex, &val, array)
 		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
 		Value::AplArray(_, _, _) => {
 			dual_dyadic_array(add, array, other)
 		}
 	}
 }
 
-pub fn add(first: &Value, other: &Value) -> Result<Box<Value>, String> {
+pub fn add(first: &Value, other: &Value) -> Result<Box<Value>, AplError> {
 	match first{
 		&Value::AplFloat(f) => {
 			add_float(f, other)
@@ -95,12 +105,11 @@ pub fn add(first: &Value, other: &Value) -> Result<Box<Value>, String> {
 		Value::AplComplex(c) => {
 			add_complex(c, other)
 		},
-		Value::AplArray(_depth, _dimensions, _values) => {
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
+		Value::AplArray(_dimensions, _values, _) => {
 			add_array(first, other)
 		}
 	}
 }
-
-pub fn eval_addition(left: &Node, right: &Node) -> Result<Box<Value>, String> {
-	eval_dyadic(add, left, right)
-}
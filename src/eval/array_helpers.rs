@@ -1,11 +1,21 @@
 use std::result;
-use crate::eval::eval::Value;
+use num::complex::{
+	Complex,
+	Complex64,
+};
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		comparison::scalars_equal,
+	},
+};
 
-pub fn simple_dyadic_array<T: Clone, F>(func: F, param: T, other: &Value) -> result::Result<Box<Value>, String> where F: Fn(T, &Value) -> result::Result<Box<Value>, String> {
+pub fn simple_dyadic_array<T: Clone, F>(func: F, param: T, other: &Value) -> result::Result<Box<Value>, AplError> where F: Fn(T, &Value) -> result::Result<Box<Value>, AplError> {
 	match other {
-		Value::AplArray(depth, dimensions, values) => {
+		Value::AplArray(dimensions, values, _) => {
 			let mut result_values: Vec<Box<Value>> = vec![];
-			let mut error_state = "".to_string();
+			let mut error_state: Option<AplError> = None;
 			let mut errored = false;
 
 			for value in values.iter() {
@@ -16,29 +26,30 @@ pub fn simple_dyadic_array<T: Clone, F>(func: F, param: T, other: &Value) -> res
 						},
 						result::Result::Err(err) => {
 							errored = true;
-							error_state = err;
+							error_state = Some(err);
 						}
 					}
 				}
 			};
 
-			if errored {
+			if let Some(error_state) = error_state {
 				result::Result::Err(error_state)
 			} else {
-				result::Result::Ok(Box::new(Value::AplArray(*depth, dimensions.clone(), result_values)))
+				result::Result::Ok(Box::new(Value::AplArray(dimensions.clone(), result_values, None)))
 			}
 		},
 		_ => {
-			panic!("This should never be reached")
+			//A scalar is its own only item
+			func(param, other)
 		}
 	}
 }
 
-pub fn inverse_simple_dyadic_array<T: Clone, F>(func: F, param: &Value, other: T) -> result::Result<Box<Value>, String> where F: Fn(&Value, T) -> result::Result<Box<Value>, String> {
+pub fn inverse_simple_dyadic_array<T: Clone, F>(func: F, param: &Value, other: T) -> result::Result<Box<Value>, AplError> where F: Fn(&Value, T) -> result::Result<Box<Value>, AplError> {
 	match param {
-		Value::AplArray(depth, dimensions, values) => {
+		Value::AplArray(dimensions, values, _) => {
 			let mut result_values: Vec<Box<Value>> = vec![];
-			let mut error_state = "".to_string();
+			let mut error_state: Option<AplError> = None;
 			let mut errored = false;
 			for value in values.iter() {
 				if !errored {
@@ -48,40 +59,46 @@ pub fn inverse_simple_dyadic_array<T: Clone, F>(func: F, param: &Value, other: T
 						},
 						result::Result::Err(err) => {
 							errored = true;
-							error_state = err;
+							error_state = Some(err);
 						}
 					}
 				}
 			}
 
-			if errored {
+			if let Some(error_state) = error_state {
 				result::Result::Err(error_state)
 			} else {
-				result::Result::Ok(Box::new(Value::AplArray(*depth, dimensions.clone(), result_values)))
+				result::Result::Ok(Box::new(Value::AplArray(dimensions.clone(), result_values, None)))
 			}
 		},
 		_ => {
-			panic!("This should never be reached")
+			func(param, other)
 		}
 	}
 }
 
-pub fn dual_dyadic_array<F>(func: F, param: &Value, other: &Value) -> result::Result<Box<Value>, String> where F: Fn(&Value, &Value) -> result::Result<Box<Value>, String> {
+pub fn dual_dyadic_array<F>(func: F, param: &Value, other: &Value) -> result::Result<Box<Value>, AplError> where F: Fn(&Value, &Value) -> result::Result<Box<Value>, AplError> {
 	match param {
-		Value::AplArray(left_depth, left_dimensions, left_values) => {
+		Value::AplArray(left_dimensions, left_values, _) => {
 			match other {
-				Value::AplArray(right_depth, right_dimensions, right_values) => {
-					//Different depths are considered a rank error
+				Value::AplArray(right_dimensions, right_values, _) => {
+					//An enclosed scalar on either side is paired with every item of the other
+					if left_dimensions.is_empty() && !right_dimensions.is_empty() {
+						return simple_dyadic_array(&func, left_values[0].as_ref(), other)
+					} else if right_dimensions.is_empty() && !left_dimensions.is_empty() {
+						return inverse_simple_dyadic_array(&func, param, right_values[0].as_ref())
+					}
+
+					//Different ranks are considered a rank error
 					//Different shapes are considered a length error
-					if left_depth != right_depth {
-						return result::Result::Err("Rank error".to_string())
+					if left_dimensions.len() != right_dimensions.len() {
+						return result::Result::Err(AplError::rank(""))
 					} else if left_dimensions != right_dimensions {
-						return result::Result::Err("Length error".to_string())
+						return result::Result::Err(AplError::length(""))
 					}
 
 					let mut result_values: Vec<Box<Value>> = vec![];
-					let mut error_state = "".to_string();
-					let mut errored = false;
+					let mut error_state: Option<AplError> = None;
 
 					for index in 0..left_values.len() {
 						match func(&left_values[index], &right_values[index]) {
@@ -89,35 +106,39 @@ pub fn dual_dyadic_array<F>(func: F, param: &Value, other: &Value) -> result::Re
 								result_values.push(val);
 							},
 							result::Result::Err(err) => {
-								error_state = err;
-								errored = true;
+								error_state = Some(err);
 								break;
 							}
 						}
 					};
 
-					if errored {
+					if let Some(error_state) = error_state {
 						result::Result::Err(error_state)
 					} else {
-						result::Result::Ok(Box::new(Value::AplArray(*left_depth, left_dimensions.clone(), result_values)))
+						result::Result::Ok(Box::new(Value::AplArray(left_dimensions.clone(), result_values, None)))
 					}
 				},
 				_ => {
-					panic!("This should never be reached")
+					//Scalar extension of the right argument
+					inverse_simple_dyadic_array(&func, param, other)
 				}
 			}
 		},
 		_ => {
-			panic!("This should never be reached")
+			//Scalar extension of the left argument, or two scalars
+			match other {
+				Value::AplArray(_, _, _) => simple_dyadic_array(&func, param, other),
+				_ => func(param, other)
+			}
 		}
 	}
 }
 
-pub fn simple_monadic_array<F>(func: F, param: &Value) -> result::Result<Box<Value>, String> where F: Fn(&Value) -> result::Result<Box<Value>, String> {
+pub fn simple_monadic_array<F>(func: F, param: &Value) -> result::Result<Box<Value>, AplError> where F: Fn(&Value) -> result::Result<Box<Value>, AplError> {
 	match param {
-		Value::AplArray(depth, dimensions, values) => {
+		Value::AplArray(dimensions, values, _) => {
 			let mut result_values: Vec<Box<Value>> = vec![];
-			let mut error_state = "".to_string();
+			let mut error_state: Option<AplError> = None;
 			let mut errored = false;
 
 			for value in values.iter() {
@@ -128,20 +149,225 @@ pub fn simple_monadic_array<F>(func: F, param: &Value) -> result::Result<Box<Val
 						},
 						result::Result::Err(err) => {
 							errored = true;
-							error_state = err;
+							error_state = Some(err);
 						}
 					}
 				}
 			}
 
-			if errored {
+			if let Some(error_state) = error_state {
 				result::Result::Err(error_state)
 			} else {
-				result::Result::Ok(Box::new(Value::AplArray(*depth, dimensions.clone(), result_values)))
+				result::Result::Ok(Box::new(Value::AplArray(dimensions.clone(), result_values, None)))
 			}
 		},
 		_ => {
-			panic!("This should never be reached")
+			func(param)
+		}
+	}
+}
+
+//Applies a function of two scalars between corresponding items at every depth, a scalar
+//or enclosed scalar on either side being paired with every item of the other
+pub fn pervasive_dyadic<F>(func: &F, first: &Value, other: &Value) -> result::Result<Box<Value>, AplError> where F: Fn(&Value, &Value) -> result::Result<Box<Value>, AplError> {
+	match (first, other) {
+		(Value::AplArray(_, _, _), _) | (_, Value::AplArray(_, _, _)) => {
+			dual_dyadic_array(|left, right| pervasive_dyadic(func, left, right), first, other)
+		},
+		_ => func(first, other)
+	}
+}
+
+//Builds an array result, unwrapping a rank 0 array of a simple scalar into that scalar
+pub fn make_array(dimensions: Vec<usize>, mut contents: Vec<Box<Value>>) -> Box<Value> {
+	if dimensions.is_empty() && contents.len() == 1 && !matches!(contents[0].as_ref(), Value::AplArray(_, _, _)) {
+		contents.pop().unwrap()
+	} else {
+		Box::new(Value::AplArray(dimensions, contents, None))
+	}
+}
+
+//Builds an array result like make_array, but one that is empty keeps the fill item it was
+//made with
+pub fn filled_array(dimensions: Vec<usize>, contents: Vec<Box<Value>>, fill: &Value) -> Box<Value> {
+	if contents.is_empty() && *fill != Value::AplInteger(0) {
+		Box::new(Value::AplArray(dimensions, contents, Some(Box::new(fill.clone()))))
+	} else {
+		make_array(dimensions, contents)
+	}
+}
+
+//Integers that overflow carry on as floats. The exact result is given when there is one,
+//and otherwise the float is worked out instead.
+pub fn integer_or_float<F>(exact: Option<isize>, approximate: F) -> Box<Value> where F: FnOnce() -> f64 {
+	match exact {
+		Some(i) => Box::new(Value::AplInteger(i)),
+		None => Box::new(Value::AplFloat(approximate()))
+	}
+}
+
+//The items of a value in row-major order, a scalar being its own single item
+pub fn ravel_list(value: &Value) -> Vec<Box<Value>> {
+	match value {
+		Value::AplArray(_, contents, _) => contents.clone(),
+		_ => vec![Box::new(value.clone())]
+	}
+}
+
+//The fill item used when an array has to be padded or is reshaped from nothing
+pub fn prototype(value: &Value) -> Value {
+	match value {
+		Value::AplArray(_, contents, fill) => {
+			match (contents.first(), fill) {
+				(Some(first), _) => type_of(first),
+				(None, Some(fill)) => type_of(fill),
+				(None, None) => Value::AplInteger(0)
+			}
+		},
+		_ => type_of(value)
+	}
+}
+
+//The structure of a value with every simple scalar replaced by its fill, a space for
+//characters and 0 for numbers
+fn type_of(value: &Value) -> Value {
+	match value {
+		Value::AplArray(dimensions, contents, fill) => {
+			let contents = contents.iter().map(|item| Box::new(type_of(item))).collect();
+			Value::AplArray(dimensions.clone(), contents, fill.as_ref().map(|fill| Box::new(type_of(fill))))
+		},
+		Value::AplChar(_) => Value::AplChar(' '),
+		_ => Value::AplInteger(0)
+	}
+}
+
+//A simple numeric scalar as a complex number, so that mixed types can share one calculation
+pub fn number(value: &Value) -> result::Result<Complex64, AplError> {
+	match *value {
+		Value::AplInteger(i) => result::Result::Ok(Complex::new(i as f64, 0.0)),
+		Value::AplFloat(f) => result::Result::Ok(Complex::new(f, 0.0)),
+		Value::AplComplex(c) => result::Result::Ok(c),
+		_ => result::Result::Err(AplError::domain("expected a number"))
+	}
+}
+
+//Reads a scalar or vector of whole numbers, as used for shapes, counts and axes
+pub fn integer_vector(value: &Value) -> result::Result<Vec<isize>, AplError> {
+	match value {
+		Value::AplArray(dimensions, contents, _) => {
+			if dimensions.len() > 1 {
+				return result::Result::Err(AplError::rank(""))
+			}
+			contents.iter().map(|item| integer_vector(item).and_then(|list| {
+				match list.as_slice() {
+					[i] => result::Result::Ok(*i),
+					_ => result::Result::Err(AplError::domain("expected a simple integer"))
+				}
+			})).collect()
+		},
+		&Value::AplInteger(i) => result::Result::Ok(vec![i]),
+		&Value::AplFloat(f) if f.fract() == 0.0 && f.abs() < isize::MAX as f64 => result::Result::Ok(vec![f as isize]),
+		_ => result::Result::Err(AplError::domain("expected an integer"))
+	}
+}
+
+//Whether two values are identical in structure and in every item. Items are compared as =
+//compares them, numbers being equal within the tolerance and never equal to characters.
+pub fn values_match(left: &Value, right: &Value, tolerance: f64) -> bool {
+	match (left, right) {
+		(Value::AplArray(left_dimensions, left_values, _), Value::AplArray(right_dimensions, right_values, _)) => {
+			left_dimensions == right_dimensions &&
+				left_values.iter().zip(right_values.iter()).all(|(l, r)| values_match(l, r, tolerance))
+		},
+		(Value::AplArray(_, _, _), _) | (_, Value::AplArray(_, _, _)) => false,
+		_ => scalars_equal(left, right, tolerance)
+	}
+}
+
+//Arrays larger than this give a limit error rather than exhausting memory
+const MAX_ARRAY_SIZE: usize = 1 << 24;
+
+//The number of items in an array of the given shape. Empty axes aside, the shape has to
+//stay under MAX_ARRAY_SIZE, so no partial product of its dimensions can overflow either.
+pub fn array_size(dimensions: &[usize]) -> result::Result<usize, AplError> {
+	let mut size: usize = 1;
+	for &dimension in dimensions.iter().filter(|&&dimension| dimension != 0) {
+		size = size.checked_mul(dimension)
+			.filter(|&size| size <= MAX_ARRAY_SIZE)
+			.ok_or_else(|| AplError::limit("array is too large"))?;
+	}
+	if dimensions.contains(&0) {
+		Ok(0)
+	} else {
+		Ok(size)
+	}
+}
+
+//Splits a shape around an axis into the number of cells before it, its length, and the
+//number of items after it, so item (o, i, j) lives at (o * length + i) * inner + j
+pub fn axis_split(dimensions: &[usize], axis: usize) -> (usize, usize, usize) {
+	let outer: usize = dimensions[..axis].iter().product();
+	let inner: usize = dimensions[axis + 1..].iter().product();
+	(outer, dimensions[axis], inner)
+}
+
+//Reads the number in an axis bracket, counted from zero rather than the index origin.
+//It may be fractional, as in the laminate ,[0.5].
+pub fn axis_number(value: &Value, origin: isize) -> result::Result<f64, AplError> {
+	let axis = match value {
+		Value::AplArray(dimensions, contents, _) if dimensions.len() <= 1 && contents.len() == 1 => contents[0].as_ref(),
+		_ => value
+	};
+	match *axis {
+		Value::AplInteger(i) => result::Result::Ok(i as f64 - origin as f64),
+		Value::AplFloat(f) => result::Result::Ok(f - origin as f64),
+		_ => result::Result::Err(AplError::axis("expected a single number"))
+	}
+}
+
+//Reads an axis that has to be one of the axes of an array of the given rank
+pub fn axis_index(value: &Value, origin: isize, rank: usize) -> result::Result<usize, AplError> {
+	let axis = axis_number(value, origin)?;
+	if axis.fract() != 0.0 || axis < 0.0 || axis >= rank as f64 {
+		return result::Result::Err(AplError::axis(""))
+	}
+	result::Result::Ok(axis as usize)
+}
+
+//Reads a scalar or vector of axes, each counted from zero
+pub fn axis_list(value: &Value, origin: isize) -> result::Result<Vec<f64>, AplError> {
+	if value.rank() > 1 {
+		return result::Result::Err(AplError::axis(""))
+	}
+	ravel_list(value).iter().map(|axis| axis_number(axis, origin)).collect()
+}
+
+//A rectangular window onto an array: each axis of the result starts at an offset into the
+//same axis of the source, which may be negative, and positions outside the source are
+//filled. Take and drop are both windows.
+pub fn window(value: &Value, dimensions: Vec<usize>, offsets: &[isize], fill: &Value) -> result::Result<Box<Value>, AplError> {
+	let size = array_size(&dimensions)?;
+	let source_shape = value.shape();
+	let source = ravel_list(value);
+	//A scalar is treated as an array of the same rank with every axis of length 1
+	let source_shape: Vec<usize> = if source_shape.is_empty() { vec![1; dimensions.len()] } else { source_shape };
+
+	let mut contents: Vec<Box<Value>> = Vec::with_capacity(size);
+	for position in 0..size {
+		let mut remainder = position;
+		let mut offset = 0;
+		let mut stride = 1;
+		let mut inside = true;
+		for axis in (0..dimensions.len()).rev() {
+			let index = (remainder % dimensions[axis]) as isize + offsets[axis];
+			remainder /= dimensions[axis];
+			if index < 0 || index >= source_shape[axis] as isize {
+				inside = false;
+			}
+			offset += index * stride as isize;
+			stride *= source_shape[axis];
 		}
+		contents.push(if inside { source[offset as usize].clone() } else { Box::new(fill.clone()) });
 	}
+	result::Result::Ok(filled_array(dimensions, contents, fill))
 }
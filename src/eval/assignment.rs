@@ -0,0 +1,81 @@
+use crate::{
+	error::AplError,
+	nodes::{
+		Function,
+		Node,
+	},
+	eval::{
+		environment::Environment,
+		eval::{
+			Value,
+			eval_node,
+		},
+	},
+};
+
+fn assign_name(target: &Node, value: &Value, env: &mut Environment) -> Result<(), AplError> {
+	match target {
+		Node::Variable(token) => {
+			match token.token_data() {
+				Some(token_data) => env.set(&token_data.string, value.clone()),
+				None => Err(AplError::syntax("invalid assignment target"))
+			}
+		},
+		_ => Err(AplError::syntax("invalid assignment target"))
+	}
+}
+
+fn assign_multiple(targets: &[Box<Node>], value: &Value, env: &mut Environment) -> Result<(), AplError> {
+	match value {
+		Value::AplArray(dimensions, contents, _) => {
+			if dimensions.len() != 1 {
+				Err(AplError::rank(""))
+			} else if contents.len() != targets.len() {
+				Err(AplError::length(""))
+			} else {
+				for (target, item) in targets.iter().zip(contents.iter()) {
+					assign_name(target, item, env)?;
+				}
+				Ok(())
+			}
+		},
+		_ => {
+			//A scalar is assigned to every name
+			for target in targets.iter() {
+				assign_name(target, value, env)?;
+			}
+			Ok(())
+		}
+	}
+}
+
+fn target_name(target: &Node) -> Option<&str> {
+	match target {
+		Node::Variable(token) => token.token_data().map(|token_data| token_data.string.as_str()),
+		_ => None
+	}
+}
+
+pub fn eval_assignment(target: &Node, value: &Node, env: &mut Environment) -> Result<Box<Value>, AplError> {
+	//⍺← only gives a default when the dfn was called without a left argument
+	if target_name(target) == Some("⍺") && env.has_local("⍺") {
+		return env.get("⍺").map(Box::new).ok_or_else(|| AplError::value("⍺ is not defined"));
+	}
+
+	let result = eval_node(value, env)?;
+	match target {
+		Node::Strand(targets) => assign_multiple(targets, &result, env)?,
+		_ => assign_name(target, &result, env)?
+	}
+	Ok(result)
+}
+
+pub fn eval_function_assignment(target: &Node, function: &Function, env: &mut Environment) -> Result<(), AplError> {
+	match target_name(target) {
+		Some(name) => {
+			env.set_function(name, function.clone());
+			Ok(())
+		},
+		None => Err(AplError::syntax("invalid assignment target"))
+	}
+}
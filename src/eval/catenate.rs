@@ -0,0 +1,172 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			array_size,
+			axis_split,
+			filled_array,
+			prototype,
+			ravel_list,
+		},
+	},
+};
+
+//The shape an argument takes in a catenation of the given rank. An argument of one rank
+//less gains a unit axis, and a scalar (None here) is extended to fit the other argument.
+fn catenation_shape(shape: Vec<usize>, rank: usize, axis: usize) -> Result<Option<Vec<usize>>, AplError> {
+	if shape.len() == rank {
+		Ok(Some(shape))
+	} else if shape.is_empty() {
+		Ok(None)
+	} else if shape.len() + 1 == rank {
+		let mut shape = shape;
+		shape.insert(axis, 1);
+		Ok(Some(shape))
+	} else {
+		Err(AplError::rank(""))
+	}
+}
+
+//Joins two arrays along an existing axis
+pub fn catenate(first: &Value, other: &Value, axis: usize) -> Result<Box<Value>, AplError> {
+	let rank = first.rank().max(other.rank()).max(1);
+	if axis >= rank {
+		return Err(AplError::axis(""))
+	}
+
+	let left = catenation_shape(first.shape(), rank, axis)?;
+	let right = catenation_shape(other.shape(), rank, axis)?;
+	let (left, right) = match (left, right) {
+		(Some(left), Some(right)) => (left, right),
+		(Some(left), None) => {
+			let mut right = left.clone();
+			right[axis] = 1;
+			(left, right)
+		},
+		(None, Some(right)) => {
+			let mut left = right.clone();
+			left[axis] = 1;
+			(left, right)
+		},
+		(None, None) => (vec![1], vec![1])
+	};
+	let same_frame = left.iter().zip(right.iter()).enumerate().all(|(i, (l, r))| i == axis || l == r);
+	if !same_frame {
+		return Err(AplError::length(""))
+	}
+
+	let mut dimensions = left.clone();
+	dimensions[axis] = left[axis] + right[axis];
+	array_size(&dimensions)?;
+
+	//A scalar, simple or enclosed, is repeated to fill its extended shape
+	let items = |value: &Value, shape: &[usize]| -> Result<Vec<Box<Value>>, AplError> {
+		match value.rank() {
+			0 => Ok(vec![ravel_list(value)[0].clone(); array_size(shape)?]),
+			_ => Ok(ravel_list(value))
+		}
+	};
+	let left_items = items(first, &left)?;
+	let right_items = items(other, &right)?;
+	let (outer, _, inner) = axis_split(&dimensions, axis);
+	let (left_length, right_length) = (left[axis] * inner, right[axis] * inner);
+	let mut contents: Vec<Box<Value>> = Vec::with_capacity(left_items.len() + right_items.len());
+	for o in 0..outer {
+		contents.extend_from_slice(&left_items[o * left_length..(o + 1) * left_length]);
+		contents.extend_from_slice(&right_items[o * right_length..(o + 1) * right_length]);
+	}
+	Ok(filled_array(dimensions, contents, &prototype(first)))
+}
+
+//Joins two arrays of the same shape along a new axis of length 2, placed before the
+//axis at the given position
+pub fn laminate(first: &Value, other: &Value, position: usize) -> Result<Box<Value>, AplError> {
+	let shape = match (first.shape(), other.shape()) {
+		(left, right) if left.is_empty() => right,
+		(left, right) if right.is_empty() => left,
+		(left, right) => {
+			if left.len() != right.len() {
+				return Err(AplError::rank(""))
+			} else if left != right {
+				return Err(AplError::length(""))
+			}
+			left
+		}
+	};
+	if position > shape.len() {
+		return Err(AplError::axis(""))
+	}
+
+	//Each argument gains a unit axis at the position, and the two are then catenated there
+	let mut dimensions = shape.clone();
+	dimensions.insert(position, 1);
+	let raise = |value: &Value| -> Result<Value, AplError> {
+		let contents = match value.rank() {
+			0 => vec![ravel_list(value)[0].clone(); array_size(&shape)?],
+			_ => ravel_list(value)
+		};
+		Ok(*filled_array(dimensions.clone(), contents, &prototype(value)))
+	};
+	catenate(&raise(first)?, &raise(other)?, position)
+}
+
+//Catenation along an axis written in brackets, which laminates when it is fractional
+pub fn catenate_axis(first: &Value, other: &Value, axis: f64) -> Result<Box<Value>, AplError> {
+	if axis.fract() == 0.0 {
+		if axis < 0.0 {
+			return Err(AplError::axis(""))
+		}
+		catenate(first, other, axis as usize)
+	} else {
+		let position = axis.ceil();
+		if position < 0.0 {
+			return Err(AplError::axis(""))
+		}
+		laminate(first, other, position as usize)
+	}
+}
+
+//Every item of an array, in row-major order, as a vector
+pub fn ravel(first: &Value) -> Result<Box<Value>, AplError> {
+	let contents = ravel_list(first);
+	Ok(filled_array(vec![contents.len()], contents, &prototype(first)))
+}
+
+//An array as a matrix, its first axis kept and the rest run together
+pub fn table(first: &Value) -> Result<Box<Value>, AplError> {
+	let shape = first.shape();
+	let dimensions = match shape.split_first() {
+		Some((rows, rest)) => vec![*rows, rest.iter().product()],
+		None => vec![1, 1]
+	};
+	Ok(filled_array(dimensions, ravel_list(first), &prototype(first)))
+}
+
+//Ravel with an axis: a fractional axis adds a new unit axis there, and a list of
+//adjacent axes runs just those together
+pub fn ravel_axis(first: &Value, axes: &[f64]) -> Result<Box<Value>, AplError> {
+	let shape = first.shape();
+	let mut dimensions = shape.clone();
+	match axes {
+		[axis] if axis.fract() != 0.0 => {
+			let position = axis.ceil();
+			if position < 0.0 || position > shape.len() as f64 {
+				return Err(AplError::axis(""))
+			}
+			dimensions.insert(position as usize, 1);
+		},
+		[] => dimensions.push(1),
+		_ => {
+			let start = axes[0];
+			let adjacent = axes.iter().enumerate().all(|(i, &axis)| axis == start + i as f64);
+			if !adjacent || start < 0.0 || start.fract() != 0.0 || start + axes.len() as f64 > shape.len() as f64 {
+				return Err(AplError::axis(""))
+			}
+			let (start, end) = (start as usize, start as usize + axes.len());
+			let merged: usize = shape[start..end].iter().product();
+			dimensions.splice(start..end, [merged]);
+		}
+	}
+	Ok(filled_array(dimensions, ravel_list(first), &prototype(first)))
+}
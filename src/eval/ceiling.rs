@@ -1,16 +1,13 @@
 use num::complex::Complex;
 use crate::{
-	nodes::Node,
+	error::AplError,
 	eval::{
-		eval::{
-			Value,
-			eval_monadic,
-		},
+		eval::Value,
 		array_helpers::simple_monadic_array,
 	},
 };
 
-pub fn ceiling(first: &Value) -> Result<Box<Value>, String> {
+pub fn ceiling(first: &Value) -> Result<Box<Value>, AplError> {
 	match first {
 		&Value::AplFloat(val) => {
 			Ok(Box::new(Value::AplInteger(val.ceil() as isize)))
@@ -21,12 +18,11 @@ pub fn ceiling(first: &Value) -> Result<Box<Value>, String> {
 		&Value::AplComplex(c) => {
 			Ok(Box::new(Value::AplComplex(Complex::new(c.re.ceil(), c.im.ceil()))))
 		},
-		Value::AplArray(_depth, _dimensions, _values) => {
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
+		Value::AplArray(_dimensions, _values, _) => {
 			simple_monadic_array(ceiling, first)
 		}
 	}
 }
-
-pub fn eval_ceiling(left: &Node) -> Result<Box<Value>, String> {
-	eval_monadic(ceiling, left)
-}
@@ -0,0 +1,144 @@
+use std::f64::consts::PI;
+use num::complex::{
+	Complex,
+	Complex64,
+};
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			pervasive_dyadic,
+			simple_monadic_array,
+		},
+		magnitude::magnitude,
+		conjugate::conjugate,
+	},
+};
+
+pub fn pi_times(first: &Value) -> Result<Box<Value>, AplError> {
+	match first {
+		&Value::AplFloat(val) => {
+			Ok(Box::new(Value::AplFloat(PI * val)))
+		},
+		&Value::AplInteger(val) => {
+			Ok(Box::new(Value::AplFloat(PI * val as f64)))
+		},
+		&Value::AplComplex(c) => {
+			Ok(Box::new(Value::AplComplex(c * PI)))
+		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
+		Value::AplArray(_dimensions, _values, _) => {
+			simple_monadic_array(pi_times, first)
+		}
+	}
+}
+
+//The left argument picks the function, an integer from ¯12 to 12
+fn selector(value: &Value) -> Result<isize, AplError> {
+	let selected = match *value {
+		Value::AplInteger(i) => Some(i),
+		Value::AplFloat(f) if f.fract() == 0.0 => Some(f as isize),
+		_ => None
+	};
+	match selected {
+		Some(k) if (-12..=12).contains(&k) => Ok(k),
+		_ => Err(AplError::domain("the left argument of ○ must be an integer from ¯12 to 12"))
+	}
+}
+
+//(X+1)×((X-1)÷X+1)*0.5, which is (¯1+X*2)*0.5 signed to follow X
+fn inverse_hypotenuse(x: Complex64) -> Complex64 {
+	let one = Complex::new(1.0, 0.0);
+	if x + one == Complex::new(0.0, 0.0) {
+		Complex::new(0.0, 0.0)
+	} else {
+		(x + one) * ((x - one) / (x + one)).sqrt()
+	}
+}
+
+fn circle_complex(k: isize, x: Complex64) -> Complex64 {
+	let one = Complex::new(1.0, 0.0);
+	match k {
+		0 => (one - x * x).sqrt(),
+		1 => x.sin(),
+		2 => x.cos(),
+		3 => x.tan(),
+		4 => (one + x * x).sqrt(),
+		5 => x.sinh(),
+		6 => x.cosh(),
+		7 => x.tanh(),
+		//-one would have a negative zero imaginary part, and take the root from below the cut
+		8 => (Complex::new(-1.0, 0.0) - x * x).sqrt(),
+		-1 => x.asin(),
+		-2 => x.acos(),
+		-3 => x.atan(),
+		-4 => inverse_hypotenuse(x),
+		-5 => x.asinh(),
+		-6 => x.acosh(),
+		-7 => x.atanh(),
+		//Taken from 0 rather than negated, so that a zero part doesn't become -0
+		-8 => Complex::new(0.0, 0.0) - circle_complex(8, x),
+		-11 => x * Complex::new(0.0, 1.0),
+		-12 => (x * Complex::new(0.0, 1.0)).exp(),
+		_ => unreachable!("the parts and identities are handled before this")
+	}
+}
+
+//The real version of each function, or NaN where the result can only be complex
+fn circle_real(k: isize, x: f64) -> f64 {
+	match k {
+		0 => (1.0 - x * x).sqrt(),
+		1 => x.sin(),
+		2 => x.cos(),
+		3 => x.tan(),
+		4 => (1.0 + x * x).sqrt(),
+		5 => x.sinh(),
+		6 => x.cosh(),
+		7 => x.tanh(),
+		-1 => x.asin(),
+		-2 => x.acos(),
+		-3 => x.atan(),
+		-4 => if x == -1.0 { 0.0 } else { (x + 1.0) * ((x - 1.0) / (x + 1.0)).sqrt() },
+		-5 => x.asinh(),
+		-6 => x.acosh(),
+		-7 => x.atanh(),
+		_ => f64::NAN
+	}
+}
+
+fn scalar_circle(left: &Value, right: &Value) -> Result<Box<Value>, AplError> {
+	let k = selector(left)?;
+	let x = match *right {
+		Value::AplInteger(i) => Complex::new(i as f64, 0.0),
+		Value::AplFloat(f) => Complex::new(f, 0.0),
+		Value::AplComplex(c) => c,
+		_ => return Err(AplError::domain("expected a number"))
+	};
+	let is_complex = matches!(right, Value::AplComplex(_));
+
+	match k {
+		9 => Ok(Box::new(if is_complex { Value::AplFloat(x.re) } else { right.clone() })),
+		10 => magnitude(right),
+		11 => Ok(Box::new(if is_complex { Value::AplFloat(x.im) } else { Value::AplInteger(0) })),
+		12 => Ok(Box::new(Value::AplFloat(x.arg()))),
+		-9 => Ok(Box::new(right.clone())),
+		-10 => conjugate(right),
+		_ => {
+			//Real arguments give real results unless the function leaves the reals, as the
+			//inverse sine of 2 does
+			let real = if is_complex { f64::NAN } else { circle_real(k, x.re) };
+			if is_complex || real.is_nan() {
+				Ok(Box::new(Value::AplComplex(circle_complex(k, x))))
+			} else {
+				Ok(Box::new(Value::AplFloat(real)))
+			}
+		}
+	}
+}
+
+pub fn circle(first: &Value, other: &Value) -> Result<Box<Value>, AplError> {
+	pervasive_dyadic(&scalar_circle, first, other)
+}
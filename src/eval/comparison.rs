@@ -0,0 +1,96 @@
+use std::cmp::Ordering;
+use num::complex::{
+	Complex,
+	Complex64,
+};
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			number,
+			pervasive_dyadic,
+		},
+	},
+};
+
+//Two numbers are tolerantly equal when they differ by no more than ⎕CT times the larger
+//magnitude. Integers against integers are always compared exactly.
+fn tolerantly_equal(left: Complex64, right: Complex64, tolerance: f64) -> bool {
+	left == right || (left - right).norm() <= tolerance * left.norm().max(right.norm())
+}
+
+fn real(value: &Value) -> Option<f64> {
+	match *value {
+		Value::AplInteger(i) => Some(i as f64),
+		Value::AplFloat(f) => Some(f),
+		_ => None
+	}
+}
+
+pub fn scalars_equal(left: &Value, right: &Value, tolerance: f64) -> bool {
+	match (left, right) {
+		(&Value::AplInteger(l), &Value::AplInteger(r)) => l == r,
+		(&Value::AplChar(l), &Value::AplChar(r)) => l == r,
+		_ => {
+			match (number(left).ok(), number(right).ok()) {
+				(Some(l), Some(r)) => tolerantly_equal(l, r, tolerance),
+				//A character never equals a number
+				_ => false
+			}
+		}
+	}
+}
+
+//Only real numbers can be ordered; complex numbers and characters give a domain error
+fn scalar_order(left: &Value, right: &Value, tolerance: f64) -> Result<Ordering, AplError> {
+	match (left, right) {
+		(&Value::AplInteger(l), &Value::AplInteger(r)) => Ok(l.cmp(&r)),
+		_ => {
+			match (real(left), real(right)) {
+				(Some(l), Some(r)) => {
+					if tolerantly_equal(Complex::new(l, 0.0), Complex::new(r, 0.0), tolerance) {
+						Ok(Ordering::Equal)
+					} else if l < r {
+						Ok(Ordering::Less)
+					} else {
+						Ok(Ordering::Greater)
+					}
+				},
+				_ => Err(AplError::domain("only real numbers can be ordered"))
+			}
+		}
+	}
+}
+
+fn boolean(truth: bool) -> Box<Value> {
+	Box::new(Value::AplInteger(truth as isize))
+}
+
+fn ordered<F>(first: &Value, other: &Value, tolerance: f64, test: F) -> Result<Box<Value>, AplError> where F: Fn(Ordering) -> bool {
+	pervasive_dyadic(&|left: &Value, right: &Value| scalar_order(left, right, tolerance).map(|order| boolean(test(order))), first, other)
+}
+
+pub fn less(first: &Value, other: &Value, tolerance: f64) -> Result<Box<Value>, AplError> {
+	ordered(first, other, tolerance, |order| order == Ordering::Less)
+}
+
+pub fn less_or_equal(first: &Value, other: &Value, tolerance: f64) -> Result<Box<Value>, AplError> {
+	ordered(first, other, tolerance, |order| order != Ordering::Greater)
+}
+
+pub fn greater_or_equal(first: &Value, other: &Value, tolerance: f64) -> Result<Box<Value>, AplError> {
+	ordered(first, other, tolerance, |order| order != Ordering::Less)
+}
+
+pub fn greater(first: &Value, other: &Value, tolerance: f64) -> Result<Box<Value>, AplError> {
+	ordered(first, other, tolerance, |order| order == Ordering::Greater)
+}
+
+pub fn equal(first: &Value, other: &Value, tolerance: f64) -> Result<Box<Value>, AplError> {
+	pervasive_dyadic(&|left: &Value, right: &Value| Ok(boolean(scalars_equal(left, right, tolerance))), first, other)
+}
+
+pub fn not_equal(first: &Value, other: &Value, tolerance: f64) -> Result<Box<Value>, AplError> {
+	pervasive_dyadic(&|left: &Value, right: &Value| Ok(boolean(!scalars_equal(left, right, tolerance))), first, other)
+}
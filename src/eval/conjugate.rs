@@ -1,15 +1,12 @@
 use crate::{
-	nodes::Node,
+	error::AplError,
 	eval::{
-		eval::{
-			Value,
-			eval_monadic,
-		},
+		eval::Value,
 		array_helpers::simple_monadic_array,
 	},
 };
 
-pub fn conjugate(first: &Value) -> Result<Box<Value>, String> {
+pub fn conjugate(first: &Value) -> Result<Box<Value>, AplError> {
 	match first{
 		&Value::AplFloat(_) | &Value::AplInteger(_) => {
 			Ok(Box::new(first.clone()))
@@ -17,12 +14,11 @@ pub fn conjugate(first: &Value) -> Result<Box<Value>, String> {
 		&Value::AplComplex(c) => {
 			Ok(Box::new(Value::AplComplex(c.conj())))
 		},
-		Value::AplArray(_depth, _dimensions, _values) => {
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
+		Value::AplArray(_dimensions, _values, _) => {
 			simple_monadic_array(conjugate, first)
 		}
 	}
 }
-
-pub fn eval_conjugate(left: &Node) -> Result<Box<Value>, String> {
-	eval_monadic(conjugate, left)
-}
@@ -0,0 +1,76 @@
+use crate::{
+	error::AplError,
+	parser::Parser,
+	nodes::{
+		DfnBody,
+		Function,
+		Node,
+		Statements,
+	},
+	eval::{
+		environment::Environment,
+		eval::{
+			Value,
+			eval_node,
+			eval_statement,
+		},
+		array_helpers::integer_vector,
+	},
+};
+
+//Runs the statements of a dfn in a fresh frame inside the environment it was defined in.
+//The first guard that holds, or the first statement that isn't an assignment, gives the result.
+pub fn call_dfn(dfn: &Function, body: &DfnBody, definition: &Environment, left: Option<&Value>, right: &Value) -> Result<Box<Value>, AplError> {
+	let statements = current_statements(body, definition)?;
+	definition.enter_call()?;
+	run_dfn(dfn, &statements, definition, left, right)
+}
+
+//The statements of a dfn as its body parses now. A name that has become a function since
+//the body was last parsed, or stopped being one, means parsing it again.
+fn current_statements(body: &DfnBody, definition: &Environment) -> Result<Statements, AplError> {
+	let functions = DfnBody::function_names(&body.tokens, |name| definition.get_function(name).is_some());
+	if body.parsed.borrow().0 != functions {
+		let statements = Parser::parse_dfn_again(body.tokens.clone(), &functions)?;
+		*body.parsed.borrow_mut() = (functions, statements);
+	}
+	Ok(body.parsed.borrow().1.clone())
+}
+
+fn run_dfn(dfn: &Function, body: &[Box<Node>], definition: &Environment, left: Option<&Value>, right: &Value) -> Result<Box<Value>, AplError> {
+	let mut local = definition.child();
+	local.set("⍵", right.clone())?;
+	if let Some(left) = left {
+		local.set("⍺", left.clone())?;
+	}
+	local.set_function_in("∇", dfn.clone(), definition);
+
+	for statement in body.iter() {
+		match statement.as_ref() {
+			Node::Guard(colon, condition, result) => {
+				let condition = eval_node(condition, &mut local)?;
+				if guard_holds(&condition).map_err(|error| error.at(colon))? {
+					return eval_node(result, &mut local);
+				}
+			},
+			_ => {
+				if let Some(result) = eval_statement(statement, &mut local)? {
+					return Ok(result);
+				}
+			}
+		}
+	}
+	Err(AplError::value("the function did not give a result"))
+}
+
+fn guard_holds(condition: &Value) -> Result<bool, AplError> {
+	if condition.rank() > 1 {
+		return Err(AplError::rank(""))
+	}
+	match integer_vector(condition)?.as_slice() {
+		[0] => Ok(false),
+		[1] => Ok(true),
+		[_] => Err(AplError::domain("a guard must be 0 or 1")),
+		_ => Err(AplError::length(""))
+	}
+}
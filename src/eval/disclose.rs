@@ -0,0 +1,68 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			array_size,
+			make_array,
+			prototype,
+			ravel_list,
+		},
+	},
+};
+
+//Turns an array of arrays into a single array, the shape of the items becoming the
+//trailing axes. Shorter items are padded out with their fill item.
+pub fn disclose(first: &Value) -> Result<Box<Value>, AplError> {
+	let items = ravel_list(first);
+	if !items.iter().any(|item| matches!(item.as_ref(), Value::AplArray(_, _, _))) {
+		return Ok(Box::new(first.clone()));
+	}
+
+	//Scalar items are treated as having the rank of the others
+	let item_rank = items.iter().map(|item| item.rank()).max().unwrap_or(0);
+	let mut item_shape: Vec<usize> = vec![1; item_rank];
+	for item in items.iter() {
+		let shape = item.shape();
+		if shape.is_empty() {
+			continue;
+		}
+		if shape.len() != item_rank {
+			return Err(AplError::rank(""))
+		}
+		for (axis, dimension) in shape.iter().enumerate() {
+			item_shape[axis] = item_shape[axis].max(*dimension);
+		}
+	}
+
+	let mut dimensions = first.shape();
+	dimensions.extend(item_shape.iter());
+	array_size(&dimensions)?;
+
+	let item_size: usize = item_shape.iter().product();
+	let mut result_values: Vec<Box<Value>> = Vec::with_capacity(items.len() * item_size);
+	for item in items.iter() {
+		let shape = if item.rank() == 0 { vec![1; item_rank] } else { item.shape() };
+		let values = ravel_list(item);
+		let fill = prototype(item);
+		for position in 0..item_size {
+			//Find the item's own index for this position, if it lies inside the item
+			let mut remainder = position;
+			let mut offset = 0;
+			let mut stride = 1;
+			let mut inside = true;
+			for axis in (0..item_rank).rev() {
+				let index = remainder % item_shape[axis];
+				remainder /= item_shape[axis];
+				if index >= shape[axis] {
+					inside = false;
+				}
+				offset += index * stride;
+				stride *= shape[axis];
+			}
+			result_values.push(if inside { values[offset].clone() } else { Box::new(fill.clone()) });
+		}
+	}
+
+	Ok(make_array(dimensions, result_values))
+}
@@ -3,12 +3,9 @@ use num::complex::{
 	Complex64,
 };
 use crate::{
-	nodes::Node,
+	error::AplError,
 	eval::{
-		eval::{
-			Value,
-			eval_dyadic,
-		},
+		eval::Value,
 		array_helpers::{
 			simple_dyadic_array, dual_dyadic_array,
 			inverse_simple_dyadic_array,
@@ -16,10 +13,10 @@ use crate::{
 	},
 };
 
-fn divide_float(f: f64, other:&Value) -> Result<Box<Value>, String> {
+fn divide_float(f: f64, other:&Value) -> Result<Box<Value>, AplError> {
 	match *other {
 		Value::AplFloat(0.0) => {
-			Err("Domain error - division by zero".to_string())
+			Err(AplError::domain("division by zero"))
 		},
 		Value::AplFloat(val) => {
 			Ok(Box::new(Value::AplFloat(f / val)))
@@ -30,38 +27,42 @@ fn divide_float(f: f64, other:&Value) -> Result<Box<Value>, String> {
 		Value::AplComplex(_val) => {
 			divide_complex(&Complex::new(f, 0.0), other)
 		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
 		Value::AplArray(_, _, _) => {
 			simple_dyadic_array(divide_float, f, other)
 		}
 	}
 }
 
-pub fn divide_integer(i: isize, other:&Value) -> Result<Box<Value>, String> {
+pub fn divide_integer(i: isize, other:&Value) -> Result<Box<Value>, AplError> {
 	match *other {
 		Value::AplFloat(_val) => {
 			divide_float(i as f64, other)
 		},
 		Value::AplInteger(0) => {
-			Err("Domain error - division by zero".to_string())
+			Err(AplError::domain("division by zero"))
 		},
 		Value::AplInteger(val) => {
-			let remainder = i % val;
-			if remainder != 0 {
-				divide_float(i as f64, &Value::AplFloat(val as f64))
-			} else {
-				Ok(Box::new(Value::AplInteger(i / val)))
+			match (i.checked_rem(val), i.checked_div(val)) {
+				(Some(0), Some(quotient)) => Ok(Box::new(Value::AplInteger(quotient))),
+				_ => divide_float(i as f64, &Value::AplFloat(val as f64))
 			}
 		},
 		Value::AplComplex(_val) => {
 			divide_complex(&Complex::new(i as f64, 0.0), other)
 		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
 		Value::AplArray(_, _, _) => {
 			simple_dyadic_array(divide_integer, i, other)
 		}
 	}
 }
 
-fn divide_complex(c: &Complex64, other: &Value) -> Result<Box<Value>, String> {
+fn divide_complex(c: &Complex64, other: &Value) -> Result<Box<Value>, AplError> {
 	match *other {
 		Value::AplFloat(f) => {
 			divide_complex(c, &Value::AplComplex(Complex::new(f, 0.0)))
@@ -72,24 +73,30 @@ fn divide_complex(c: &Complex64, other: &Value) -> Result<Box<Value>, String> {
 		Value::AplComplex(other_c) => {
 			Ok(Box::new(Value::AplComplex(c / other_c))) //FIXME: Doesn't catch divide by zero
 		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
 		Value::AplArray(_, _, _) => {
 			simple_dyadic_array(divide_complex, c, other)
 		}
 	}
 }
 
-fn divide_array(array: &Value, other: &Value) -> Result<Box<Value>, String> {
+fn divide_array(array: &Value, other: &Value) -> Result<Box<Value>, AplError> {
 	match other {
 		&Value::AplFloat(_) |  &Value::AplInteger(_) | &Value::AplComplex(_) => {
 			inverse_simple_dyadic_array(divide, array, other)
 		},
+		&Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
 		&Value::AplArray(_, _, _) => {
 			dual_dyadic_array(divide, array, other)
 		}
 	}
 }
 
-pub fn divide(first: &Value, other: &Value) -> Result<Box<Value>, String> {
+pub fn divide(first: &Value, other: &Value) -> Result<Box<Value>, AplError> {
 	match first{
 		&Value::AplFloat(f) => {
 			divide_float(f, other)
@@ -100,12 +107,11 @@ pub fn divide(first: &Value, other: &Value) -> Result<Box<Value>, String> {
 		Value::AplComplex(c) => {
 			divide_complex(c, other)
 		},
-		Value::AplArray(_depth, _dimensions, _values) => {
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
+		Value::AplArray(_dimensions, _values, _) => {
 			divide_array(first, other)
 		}
 	}
 }
-
-pub fn eval_division(left: &Node, right: &Node) -> Result<Box<Value>, String> {
-	eval_dyadic(divide, left, right)
-}
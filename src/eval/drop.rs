@@ -0,0 +1,62 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			axis_split,
+			filled_array,
+			prototype,
+			window,
+		},
+		take::{
+			axis_counts,
+			source_shape,
+		},
+	},
+};
+
+//A↓B drops A[i] items along each axis i, from the end when the count is negative
+pub fn drop(first: &Value, other: &Value) -> Result<Box<Value>, AplError> {
+	let counts = axis_counts(first, other)?;
+	let shape = source_shape(other, &counts);
+
+	let mut dimensions = shape.clone();
+	let mut offsets: Vec<isize> = vec![0; shape.len()];
+	for (axis, &count) in counts.iter().enumerate() {
+		dimensions[axis] = shape[axis].saturating_sub(count.unsigned_abs());
+		if count > 0 {
+			offsets[axis] = count;
+		}
+	}
+	window(other, dimensions, &offsets, &prototype(other))
+}
+
+//↓B splits an array into vectors along an axis, the last unless another is given
+pub fn split(first: &Value, axis: usize) -> Result<Box<Value>, AplError> {
+	match first {
+		Value::AplArray(dimensions, values, _) if !dimensions.is_empty() => {
+			if axis >= dimensions.len() {
+				return Err(AplError::axis(""))
+			}
+			let (outer, length, inner) = axis_split(dimensions, axis);
+			let mut result_dimensions = dimensions.clone();
+			result_dimensions.remove(axis);
+			//Each vector keeps the argument's fill item, and an empty result keeps a vector of them
+			let fill = prototype(first);
+			let item_fill = filled_array(vec![length], vec![Box::new(fill.clone()); length], &fill);
+
+			let mut result_values: Vec<Box<Value>> = Vec::with_capacity(outer * inner);
+			for o in 0..outer {
+				for j in 0..inner {
+					let vector: Vec<Box<Value>> = (0..length).map(|i| values[(o * length + i) * inner + j].clone()).collect();
+					result_values.push(filled_array(vec![length], vector, &fill));
+				}
+			}
+			Ok(filled_array(result_dimensions, result_values, &item_fill))
+		},
+		_ => {
+			//Splitting a scalar leaves it alone
+			Ok(Box::new(first.clone()))
+		}
+	}
+}
@@ -0,0 +1,44 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			make_array,
+			ravel_list,
+		},
+	},
+};
+
+//Applies func to every item of the argument, keeping each result as an item of the result
+pub fn each_monadic<F>(mut func: F, first: &Value) -> Result<Box<Value>, AplError> where F: FnMut(&Value) -> Result<Box<Value>, AplError> {
+	let mut result_values: Vec<Box<Value>> = vec![];
+	for value in ravel_list(first).iter() {
+		result_values.push(func(value)?);
+	}
+	Ok(make_array(first.shape(), result_values))
+}
+
+//Applies func between corresponding items, a scalar being paired with every item of the other side
+pub fn each_dyadic<F>(mut func: F, first: &Value, other: &Value) -> Result<Box<Value>, AplError> where F: FnMut(&Value, &Value) -> Result<Box<Value>, AplError> {
+	let left_values = ravel_list(first);
+	let right_values = ravel_list(other);
+
+	let dimensions = if first.rank() == 0 {
+		other.shape()
+	} else if other.rank() == 0 || first.shape() == other.shape() {
+		first.shape()
+	} else if first.rank() != other.rank() {
+		return Err(AplError::rank(""))
+	} else {
+		return Err(AplError::length(""))
+	};
+
+	let size: usize = dimensions.iter().product();
+	let mut result_values: Vec<Box<Value>> = Vec::with_capacity(size);
+	for index in 0..size {
+		let left = if first.rank() == 0 { &left_values[0] } else { &left_values[index] };
+		let right = if other.rank() == 0 { &right_values[0] } else { &right_values[index] };
+		result_values.push(func(left, right)?);
+	}
+	Ok(make_array(dimensions, result_values))
+}
@@ -0,0 +1,16 @@
+use crate::{
+	error::AplError,
+	eval::eval::Value,
+};
+
+//Wraps an array up as a scalar. A simple scalar encloses to itself.
+pub fn enclose(first: &Value) -> Result<Box<Value>, AplError> {
+	match first {
+		Value::AplArray(_, _, _) => {
+			Ok(Box::new(Value::AplArray(vec![], vec![Box::new(first.clone())], None)))
+		},
+		_ => {
+			Ok(Box::new(first.clone()))
+		}
+	}
+}
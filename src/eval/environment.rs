@@ -0,0 +1,267 @@
+use std::{
+	cell::{
+		Cell,
+		RefCell,
+	},
+	collections::HashMap,
+	hint,
+	rc::Rc,
+};
+use crate::{
+	error::AplError,
+	nodes::Function,
+	eval::{
+		eval::Value,
+		random::{
+			DEFAULT_RANDOM_LINK,
+			is_random_link,
+		},
+	},
+};
+
+#[derive(Clone)]
+enum Binding {
+	Array(Value),
+	//A function, with the scope it was defined in when that isn't the frame holding it
+	Function(Function, Option<Rc<RefCell<Frame>>>),
+}
+
+struct Frame {
+	bindings: HashMap<String, Binding>,
+	parent: Option<Rc<RefCell<Frame>>>
+}
+
+//A chain of frames, innermost first. Names are looked up outwards through the frames a
+//function was defined in, so dfns see the names around their definition, not their caller's.
+pub struct Environment {
+	scope: Rc<RefCell<Frame>>,
+	//Shared by every environment of one evaluator
+	stack: Rc<Stack>
+}
+
+//How much of the native stack evaluation may use, and where it began using it. Function
+//calls are checked against it, so deep recursion gives a limit error rather than
+//overflowing the stack, however much stack the thread has.
+struct Stack {
+	base: Cell<usize>,
+	limit: Cell<usize>
+}
+
+//The stack Rust gives a new thread, which an evaluator assumes it has unless told otherwise
+pub const DEFAULT_STACK_SIZE: usize = 2 * 1024 * 1024;
+
+//The stack the session runs the evaluator with, so that deep recursion has room
+pub const EVALUATOR_STACK_SIZE: usize = 256 * 1024 * 1024;
+
+//Kept spare below the limit for the call that reaches it, whose statements may still be
+//nested as deeply as the parser allows
+const STACK_RESERVE: usize = 1024 * 1024;
+
+//Roughly where the stack has got to. It grows downwards, so the further in, the lower.
+fn stack_position() -> usize {
+	let marker = 0u8;
+	hint::black_box(&marker) as *const u8 as usize
+}
+
+impl Environment {
+	pub fn new() -> Environment {
+		let mut bindings = HashMap::new();
+		bindings.insert("⎕IO".to_string(), Binding::Array(Value::AplInteger(1)));
+		bindings.insert("⎕CT".to_string(), Binding::Array(Value::AplFloat(1e-14)));
+		bindings.insert("⎕CX".to_string(), Binding::Array(Value::AplInteger(1)));
+		bindings.insert("⎕RL".to_string(), Binding::Array(Value::AplInteger(DEFAULT_RANDOM_LINK)));
+		Environment {
+			scope: Rc::new(RefCell::new(Frame {
+				bindings,
+				parent: None
+			})),
+			stack: Rc::new(Stack {
+				base: Cell::new(stack_position()),
+				limit: Cell::new(DEFAULT_STACK_SIZE - STACK_RESERVE)
+			})
+		}
+	}
+
+	//A new, empty local frame inside this one
+	pub fn child(&self) -> Environment {
+		Environment {
+			scope: Rc::new(RefCell::new(Frame {
+				bindings: HashMap::new(),
+				parent: Some(self.scope.clone())
+			})),
+			stack: self.stack.clone()
+		}
+	}
+
+	fn with_scope(&self, scope: Rc<RefCell<Frame>>) -> Environment {
+		Environment {
+			scope,
+			stack: self.stack.clone()
+		}
+	}
+
+	//The size of the stack of the thread the evaluator runs on
+	pub fn set_stack_size(&self, size: usize) {
+		self.stack.limit.set(size.saturating_sub(STACK_RESERVE));
+	}
+
+	//Marks where evaluation starts using the stack, before a program is parsed and run
+	pub fn begin_evaluation(&self) {
+		self.stack.base.set(stack_position());
+	}
+
+	//Checks before a function call that the stack has room for it
+	pub fn enter_call(&self) -> Result<(), AplError> {
+		if self.stack.base.get().saturating_sub(stack_position()) > self.stack.limit.get() {
+			return Err(AplError::limit("functions are nested too deeply"));
+		}
+		Ok(())
+	}
+
+	fn lookup(&self, name: &str) -> Option<(Binding, Rc<RefCell<Frame>>)> {
+		let mut frame = self.scope.clone();
+		loop {
+			let parent = {
+				let borrowed = frame.borrow();
+				if let Some(binding) = borrowed.bindings.get(name) {
+					return Some((binding.clone(), frame.clone()));
+				}
+				borrowed.parent.clone()
+			};
+			frame = parent?;
+		}
+	}
+
+	fn root(&self) -> Rc<RefCell<Frame>> {
+		let mut frame = self.scope.clone();
+		loop {
+			let parent = frame.borrow().parent.clone();
+			match parent {
+				Some(parent) => frame = parent,
+				None => return frame
+			}
+		}
+	}
+
+	pub fn get(&self, name: &str) -> Option<Value> {
+		match self.lookup(name) {
+			Some((Binding::Array(value), _)) => Some(value),
+			_ => None
+		}
+	}
+
+	//The function bound to a name, and the environment it should be called in
+	pub fn get_function(&self, name: &str) -> Option<(Function, Environment)> {
+		match self.lookup(name) {
+			Some((Binding::Function(function, Some(scope)), _)) => Some((function, self.with_scope(scope))),
+			Some((Binding::Function(function, None), frame)) => Some((function, self.with_scope(frame))),
+			_ => None
+		}
+	}
+
+	pub fn has_local(&self, name: &str) -> bool {
+		self.scope.borrow().bindings.contains_key(name)
+	}
+
+	//Assignment is always local, except to system variables which live in the outermost frame
+	pub fn set(&mut self, name: &str, value: Value) -> Result<(), AplError> {
+		if name.starts_with('⎕') {
+			validate_system_variable(name, &value)?;
+			self.root().borrow_mut().bindings.insert(name.to_string(), Binding::Array(value));
+		} else {
+			self.scope.borrow_mut().bindings.insert(name.to_string(), Binding::Array(value));
+		}
+		Ok(())
+	}
+
+	pub fn set_function(&mut self, name: &str, function: Function) {
+		self.scope.borrow_mut().bindings.insert(name.to_string(), Binding::Function(function, None));
+	}
+
+	//Binds a function that should be called in another environment, as ∇ is within a dfn
+	pub fn set_function_in(&mut self, name: &str, function: Function, definition: &Environment) {
+		self.scope.borrow_mut().bindings.insert(name.to_string(), Binding::Function(function, Some(definition.scope.clone())));
+	}
+
+	//Every name currently bound to a function, so the parser can tell F X from a strand
+	pub fn function_names(&self) -> Vec<String> {
+		let mut names: Vec<String> = vec![];
+		let mut frame = Some(self.scope.clone());
+		while let Some(current) = frame {
+			let borrowed = current.borrow();
+			for (name, binding) in borrowed.bindings.iter() {
+				if matches!(binding, Binding::Function(_, _)) && self.get_function(name).is_some() {
+					names.push(name.clone());
+				}
+			}
+			frame = borrowed.parent.clone();
+		}
+		names
+	}
+
+	pub fn index_origin(&self) -> isize {
+		match self.get("⎕IO") {
+			Some(Value::AplInteger(origin)) => origin,
+			_ => 1
+		}
+	}
+
+	//How far apart two floats may be, relative to the larger, and still compare equal
+	pub fn comparison_tolerance(&self) -> f64 {
+		match self.get("⎕CT") {
+			Some(Value::AplFloat(tolerance)) => tolerance,
+			_ => 0.0
+		}
+	}
+
+	//Whether functions of real numbers may give complex results, as ⍟¯1 does, rather than
+	//a domain error
+	pub fn complex_results(&self) -> bool {
+		!matches!(self.get("⎕CX"), Some(Value::AplInteger(0)))
+	}
+
+	//The state of the random number generator behind ? and its seed when assigned
+	pub fn random_link(&self) -> isize {
+		match self.get("⎕RL") {
+			Some(Value::AplInteger(link)) => link,
+			_ => DEFAULT_RANDOM_LINK
+		}
+	}
+}
+
+impl Default for Environment {
+	fn default() -> Environment {
+		Environment::new()
+	}
+}
+
+fn validate_system_variable(name: &str, value: &Value) -> Result<(), AplError> {
+	match name {
+		"⎕IO" => {
+			match value {
+				Value::AplInteger(0) | Value::AplInteger(1) => Ok(()),
+				_ => Err(AplError::domain("⎕IO must be 0 or 1"))
+			}
+		},
+		"⎕CT" => {
+			match value {
+				Value::AplInteger(0) => Ok(()),
+				Value::AplFloat(tolerance) if (0.0..1.0).contains(tolerance) => Ok(()),
+				_ => Err(AplError::domain("⎕CT must be at least 0 and less than 1"))
+			}
+		},
+		"⎕CX" => {
+			match value {
+				Value::AplInteger(0) | Value::AplInteger(1) => Ok(()),
+				_ => Err(AplError::domain("⎕CX must be 0 or 1"))
+			}
+		},
+		"⎕RL" => {
+			match *value {
+				Value::AplInteger(link) if is_random_link(link) => Ok(()),
+				_ => Err(AplError::domain("⎕RL must be an integer from 1 to 2147483646"))
+			}
+		},
+		_ => Err(AplError::value(format!("{} is not a system variable", name)))
+	}
+}
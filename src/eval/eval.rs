@@ -4,12 +4,18 @@ use num::complex::{
 	Complex64,
 };
 use crate::{
+	error::AplError,
 	tokenizer::Token,
 	parser::Parser,
 	nodes::{
 		Node,
 		EvalNode,
 	},
+	eval::{
+		environment::Environment,
+		assignment::eval_function_assignment,
+		array_helpers::filled_array,
+	},
 };
 
 pub trait Printable {
@@ -17,12 +23,41 @@ pub trait Printable {
 	fn to_typed_string(&self) -> String;
 }
 
+//Arrays carry their shape and their items in row-major order. The rank is the
+//length of the shape, nesting depth is found by walking the items. An empty array has no
+//items to take a fill item from, so it carries its own when that isn't 0, as for ''.
 #[derive(PartialEq, Clone)]
 pub enum Value {
 	AplFloat(f64),
 	AplInteger(isize),
 	AplComplex(Complex64),
-	AplArray(usize, Vec<usize>, Vec<Box<Value>>)
+	AplChar(char),
+	AplArray(Vec<usize>, Vec<Box<Value>>, Option<Box<Value>>)
+}
+
+impl Value {
+	pub fn shape(&self) -> Vec<usize> {
+		match self {
+			Value::AplArray(dimensions, _, _) => dimensions.clone(),
+			_ => vec![]
+		}
+	}
+
+	pub fn rank(&self) -> usize {
+		match self {
+			Value::AplArray(dimensions, _, _) => dimensions.len(),
+			_ => 0
+		}
+	}
+
+	pub fn depth(&self) -> usize {
+		match self {
+			Value::AplArray(_, contents, _) => {
+				1 + contents.iter().map(|item| item.depth()).max().unwrap_or(0)
+			},
+			_ => 0
+		}
+	}
 }
 
 impl Printable for Value {
@@ -34,16 +69,18 @@ impl Printable for Value {
 			Value::AplInteger(i) => {
 				format!("{}", i)
 			},
-			Value::AplArray(depth, ref _dimensions, ref contents) => {
-				if depth != 1 {
-					panic!("Multidimensional arrays aren't yet supported");
-				}
-				let segments: Vec<String> = contents.iter().map(|item| item.to_string()).collect();
+			Value::AplArray(ref dimensions, ref contents, _) => {
+				let lines: Vec<String> = array_to_lines(dimensions, contents).iter()
+					.map(|line| line.trim_end().to_string())
+					.collect();
 
-				segments.join(" ")
+				lines.join("\n")
 			},
 			Value::AplComplex(j) => {
 				format!("{}J{}", j.re, j.im)
+			},
+			Value::AplChar(c) => {
+				c.to_string()
 			}
 		}
 	}
@@ -56,54 +93,197 @@ impl Printable for Value {
 			Value::AplInteger(_) => {
 				format!("INTEGER({})", self.to_string())
 			},
-			Value::AplArray(_, _, _) => {
-				format!("ARRAY({})", self.to_string())
+			Value::AplArray(ref dimensions, _, _) => {
+				let shape: Vec<String> = dimensions.iter().map(|d| d.to_string()).collect();
+				format!("ARRAY[{}]({})", shape.join(" "), self.to_string())
 			},
 			Value::AplComplex(_) => {
 				format!("COMPLEX({})", self.to_string())
+			},
+			Value::AplChar(_) => {
+				format!("CHAR({})", self.to_string())
 			}
 		}
 	}
 }
 
-pub fn eval_node(node: &Node) -> Result<Box<Value>,String> {
+//Each item is laid out as a block of lines, so that nested items and the
+//columns of a matrix can be aligned on a grid.
+fn value_to_lines(value: &Value) -> Vec<String> {
+	match value {
+		Value::AplArray(dimensions, contents, _) => array_to_lines(dimensions, contents),
+		_ => vec![value.to_string()]
+	}
+}
+
+fn array_to_lines(dimensions: &[usize], contents: &[Box<Value>]) -> Vec<String> {
+	match dimensions.len() {
+		0 => {
+			//An enclosed scalar is set in by a space, as APL2 does
+			contents.iter().flat_map(|item| value_to_lines(item))
+				.map(|line| format!(" {}", line))
+				.collect()
+		},
+		1 => matrix_to_lines(1, dimensions[0], contents),
+		2 => matrix_to_lines(dimensions[0], dimensions[1], contents),
+		rank => {
+			//Subarrays along the first axis are separated by rank-2 blank lines
+			let cell_size: usize = dimensions[1..].iter().product();
+			let mut lines: Vec<String> = vec![];
+			for index in 0..dimensions[0] {
+				if index > 0 {
+					lines.extend(std::iter::repeat_n(String::new(), rank - 2));
+				}
+				let cell = &contents[index * cell_size..(index + 1) * cell_size];
+				lines.extend(array_to_lines(&dimensions[1..], cell));
+			}
+			lines
+		}
+	}
+}
+
+fn matrix_to_lines(rows: usize, columns: usize, contents: &[Box<Value>]) -> Vec<String> {
+	if columns == 0 {
+		return vec![String::new(); rows];
+	}
+
+	let cells: Vec<Vec<String>> = contents.iter().map(|item| value_to_lines(item)).collect();
+
+	let widths: Vec<usize> = (0..columns).map(|column| {
+		(0..rows).flat_map(|row| cells[row * columns + column].iter())
+			.map(|line| line.chars().count())
+			.max()
+			.unwrap_or(0)
+	}).collect();
+
+	//Columns holding nested items get an extra space either side to set them apart, while
+	//neighbouring columns of characters run together as text
+	let nested: Vec<bool> = (0..columns).map(|column| {
+		(0..rows).any(|row| matches!(contents[row * columns + column].as_ref(), Value::AplArray(_, _, _)))
+	}).collect();
+	let characters: Vec<bool> = (0..columns).map(|column| {
+		(0..rows).all(|row| matches!(contents[row * columns + column].as_ref(), Value::AplChar(_)))
+	}).collect();
+
+	let mut lines: Vec<String> = vec![];
+	for row in 0..rows {
+		let height = (0..columns).map(|column| cells[row * columns + column].len()).max().unwrap_or(0);
+		for line_index in 0..height {
+			let mut line = String::new();
+			for (column, width) in widths.iter().enumerate() {
+				if column == 0 {
+					if nested[column] {
+						line.push(' ');
+					}
+				} else if nested[column - 1] || nested[column] {
+					line.push_str("  ");
+				} else if characters[column - 1] && characters[column] {
+					//No separator
+				} else {
+					line.push(' ');
+				}
+
+				let index = row * columns + column;
+				let text = cells[index].get(line_index).map(|text| text.as_str()).unwrap_or("");
+				match contents[index].as_ref() {
+					Value::AplArray(_, _, _) => line.push_str(&format!("{:<width$}", text, width = width)),
+					_ => line.push_str(&format!("{:>width$}", text, width = width))
+				}
+			}
+			lines.push(line);
+		}
+	}
+	lines
+}
+
+pub fn eval_node(node: &Node, env: &mut Environment) -> Result<Box<Value>, AplError> {
 	match node {
-		Node::Array(nodes) => Ok(eval_array(nodes)),
-		_ => node.eval()
+		Node::Array(nodes) => eval_array(nodes),
+		Node::Strand(nodes) => eval_strand(nodes, env),
+		Node::Variable(token) => eval_variable(token, env),
+		Node::Zilde(_) => Ok(Box::new(Value::AplArray(vec![0], vec![], None))),
+		_ => node.eval(env)
 	}
 }
 
-fn eval_array(tokens: &[Box<Token>]) -> Box<Value> {
-	if tokens.len() == 1 {
-		match &tokens[0].as_ref() {
-			&Token::Number(token_data) => {
-				eval_number(&token_data.string)
-			},
-			_ => {
-				panic!("Unsupported type in array")
+fn eval_variable(token: &Token, env: &Environment) -> Result<Box<Value>, AplError> {
+	//Names, and the ⍺ and ⍵ arguments of a dfn
+	match token.token_data() {
+		Some(token_data) => {
+			match env.get(&token_data.string) {
+				Some(value) => Ok(Box::new(value)),
+				None => Err(AplError::value(format!("{} is not defined", token_data.string)).at(token))
 			}
+		},
+		None => Err(AplError::syntax("unsupported type in variable"))
+	}
+}
+
+//Evaluates a whole statement. Assignments are shy, so they give back no value to display.
+pub fn eval_statement(node: &Node, env: &mut Environment) -> Result<Option<Box<Value>>, AplError> {
+	match node {
+		Node::Assignment(_, _, _) => {
+			eval_node(node, env).map(|_| None)
+		},
+		Node::FunctionAssignment(_, target, function) => {
+			eval_function_assignment(target, function, env).map(|_| None)
+		},
+		_ => {
+			eval_node(node, env).map(Some)
 		}
+	}
+}
+
+fn eval_strand(nodes: &[Box<Node>], env: &mut Environment) -> Result<Box<Value>, AplError> {
+	//Items are evaluated right to left, like everything else in APL
+	let mut array_contents: Vec<Box<Value>> = vec![];
+	for node in nodes.iter().rev() {
+		array_contents.push(eval_node(node, env)?);
+	}
+	array_contents.reverse();
+	Ok(Box::new(Value::AplArray(vec![array_contents.len()], array_contents, None)))
+}
+
+fn eval_array(tokens: &[Box<Token>]) -> Result<Box<Value>, AplError> {
+	if tokens.len() == 1 {
+		eval_literal(&tokens[0])
 	} else {
 		let mut array_contents: Vec<Box<Value>> = vec![];
 		for token in tokens.iter() {
-			match token.as_ref() {
-				Token::Number(token_data) => {
-					array_contents.push(eval_number(&token_data.string))
-				},
-				_ => {
-					panic!("Unsupported type in array")
-				}
-			}
+			array_contents.push(eval_literal(token)?);
 		}
-		Box::new(Value::AplArray(1, vec![array_contents.len()], array_contents))
+		Ok(Box::new(Value::AplArray(vec![array_contents.len()], array_contents, None)))
+	}
+}
+
+fn eval_literal(token: &Token) -> Result<Box<Value>, AplError> {
+	match token {
+		Token::Number(token_data) => {
+			eval_number(&token_data.string).map_err(|error| error.at(token))
+		},
+		Token::String(token_data) => {
+			Ok(eval_string(&token_data.string))
+		},
+		_ => Err(AplError::syntax("unsupported type in array").at(token))
+	}
+}
+
+//A single character is a scalar, any other string a character vector
+fn eval_string(token_string: &str) -> Box<Value> {
+	let mut characters: Vec<Box<Value>> = token_string.chars().map(|c| Box::new(Value::AplChar(c))).collect();
+	if characters.len() == 1 {
+		characters.remove(0)
+	} else {
+		//'' is still a character vector, and is filled with spaces
+		filled_array(vec![characters.len()], characters, &Value::AplChar(' '))
 	}
 }
 
-fn eval_number(token_string: &str) -> Box<Value> {
+fn eval_number(token_string: &str) -> Result<Box<Value>, AplError> {
 	match token_string.find('J') {
 		//FIXME: This needs to handle exponents
 		Some(pos) => {
-			eval_complex(&token_string[..pos], &token_string[..pos + 1])
+			eval_complex(&token_string[..pos], &token_string[pos + 1..])
 		},
 		None => {
 			match token_string.find('.') {
@@ -126,109 +306,106 @@ fn get_string_and_sign(token_string: &str) -> (&str, bool){
 	}
 }
 
-fn eval_complex(left: &str, right: &str) -> Box<Value> {
-	let (left_match_string, left_is_negative) = get_string_and_sign(left);
-	let (right_match_string, right_is_negative) = get_string_and_sign(right);
-
-	match (left_match_string.parse::<f64>().ok(), right_match_string.parse::<f64>().ok()) {
-		(Some(left_float), Some(right_float)) => {
-			let left_final = if left_is_negative {
-				-left_float
-			} else {
-				left_float
-			};
-			let right_final = if right_is_negative {
-				-right_float
-			} else {
-				right_float
-			};
-			Box::new(Value::AplComplex(Complex::new(left_final, right_final)))
-		},
-		_ => {
-			panic!("Bad complex {} {}", left, right)
-		}
-	}
-}
-
-fn eval_float(token_string: &str) -> Box<Value> {
+fn parse_float(token_string: &str) -> Result<f64, AplError> {
 	let (match_string, is_negative) = get_string_and_sign(token_string);
 
 	match match_string.parse::<f64>().ok() {
 		Some(fl) => {
 			if is_negative {
-				Box::new(Value::AplFloat(-fl))
+				Ok(-fl)
 			} else {
-				Box::new(Value::AplFloat(fl))
+				Ok(fl)
 			}
 		},
 		None => {
-			panic!("Bad float {}", token_string)
+			Err(AplError::syntax(format!("bad number {}", token_string)))
 		}
 	}
 }
 
-fn eval_int(token_string: &str) -> Box<Value> {
+fn eval_complex(left: &str, right: &str) -> Result<Box<Value>, AplError> {
+	Ok(Box::new(Value::AplComplex(Complex::new(parse_float(left)?, parse_float(right)?))))
+}
+
+fn eval_float(token_string: &str) -> Result<Box<Value>, AplError> {
+	parse_float(token_string).map(|fl| Box::new(Value::AplFloat(fl)))
+}
+
+fn eval_int(token_string: &str) -> Result<Box<Value>, AplError> {
 	let (match_string, is_negative) = get_string_and_sign(token_string);
 
 	match match_string.parse::<isize>().ok() {
 		Some(i) => {
 			if is_negative {
-				Box::new(Value::AplInteger(-i))
+				Ok(Box::new(Value::AplInteger(-i)))
 			} else {
-				Box::new(Value::AplInteger(i))
+				Ok(Box::new(Value::AplInteger(i)))
 			}
 		},
 		None => {
-			panic!("Bad int {}", token_string)
+			//Too large for an integer, so it is held as a float instead
+			eval_float(token_string)
 		}
 	}
 }
 
-pub fn eval_dyadic<F>(func: F, left: &Node, right: &Node) -> Result<Box<Value>, String> where F: Fn(&Value, &Value) -> Result<Box<Value>, String> {
-	match eval_node(left) {
-		Ok(left) => {
-			match eval_node(right) {
-				Ok(right) => {
-					func(&left, &right)
-				},
-				Err(msg) => {
-					Err(msg)
-				}
-			}
-		},
-		Err(msg) => {
-			Err(msg)
-		}
-	}
-}
-
-pub fn eval_monadic<F>(func: F, left: &Node) -> Result<Box<Value>, String> where F: Fn(&Value) -> Result<Box<Value>, String> {
-	eval_node(left).and_then(|result| {
-		func(&result)
-	})
-}
-
 pub struct Evaluator {
-	parser: Box<Parser>
+	environment: Environment
 }
 
 impl Evaluator {
 
-	pub fn new(input_string: String) -> Evaluator {
+	//An evaluator for a thread with the stack Rust gives new threads, DEFAULT_STACK_SIZE
+	pub fn new() -> Evaluator {
 		Evaluator {
-			parser: Box::new(Parser::new(input_string))
+			environment: Environment::new()
 		}
 	}
 
-	pub fn eval(&mut self) -> Result<Box<Value>, String> {
-		let tree = self.parser.parse_next_statement(); //TODO: Should loop?
-		match tree {
-			Ok(node) => {
-				eval_node(&node)
-			},
-			Err(msg) => {
-				Err(msg)
+	//An evaluator for a thread with a stack of the given size. Recursion can go deeper the
+	//more stack there is, but the evaluator has to be told, as it can't find out itself.
+	pub fn with_stack_size(size: usize) -> Evaluator {
+		let evaluator = Evaluator::new();
+		evaluator.environment.set_stack_size(size);
+		evaluator
+	}
+
+	//Runs every statement in the source, giving back the value of the last one
+	pub fn eval(&mut self, input_string: String) -> Result<Option<Box<Value>>, AplError> {
+		self.environment.begin_evaluation();
+		let mut parser = self.parser(input_string);
+		let mut result = None;
+		while let Some(node) = parser.parse_next_statement()? {
+			result = eval_statement(&node, &mut self.environment)?;
+		}
+		Ok(result)
+	}
+
+	//Runs every statement in the source in order, handing each value that isn't shy to
+	//output as it is produced. The program stops at the first error.
+	pub fn run_program<F>(&mut self, source: String, mut output: F) -> Result<(), AplError> where F: FnMut(&Value) {
+		self.environment.begin_evaluation();
+		let mut parser = self.parser(source);
+		while let Some(node) = parser.parse_next_statement()? {
+			if let Some(value) = eval_statement(&node, &mut self.environment)? {
+				output(&value);
 			}
 		}
+		Ok(())
+	}
+
+	//A parser that knows which names already hold functions
+	fn parser(&self, source: String) -> Parser {
+		let mut parser = Parser::new(source);
+		for name in self.environment.function_names() {
+			parser.declare_function(&name);
+		}
+		parser
+	}
+}
+
+impl Default for Evaluator {
+	fn default() -> Evaluator {
+		Evaluator::new()
 	}
 }
@@ -1,17 +1,14 @@
 use std::f64::consts::E;
 use num::complex::Complex;
 use crate::{
-	nodes::Node,
+	error::AplError,
 	eval::{
-		eval::{
-			Value,
-			eval_monadic,
-		},
+		eval::Value,
 		array_helpers::simple_monadic_array,
 	},
 };
 
-pub fn exponential(first: &Value) -> Result<Box<Value>, String> {
+pub fn exponential(first: &Value) -> Result<Box<Value>, AplError> {
 	match first {
 		&Value::AplFloat(val) => {
 			Ok(Box::new(Value::AplFloat(val.exp())))
@@ -27,12 +24,11 @@ pub fn exponential(first: &Value) -> Result<Box<Value>, String> {
 			let result = Complex::new(powed, 0.0) * complex;
 			Ok(Box::new(Value::AplComplex(result)))
 		},
-		Value::AplArray(_depth, _dimensions, _values) => {
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
+		Value::AplArray(_dimensions, _values, _) => {
 			simple_monadic_array(exponential, first)
 		}
 	}
 }
-
-pub fn eval_exponential(left: &Node) -> Result<Box<Value>, String> {
-	eval_monadic(exponential, left)
-}
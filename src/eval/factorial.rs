@@ -0,0 +1,208 @@
+use std::f64::consts::PI;
+use num::complex::{
+	Complex,
+	Complex64,
+};
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			number,
+			pervasive_dyadic,
+			simple_monadic_array,
+		},
+	},
+};
+
+//Lanczos approximation with g = 7 and nine coefficients, good to about 15 digits
+const LANCZOS_G: f64 = 7.0;
+const LANCZOS_COEFFICIENTS: [f64; 9] = [
+	0.999_999_999_999_809_9,
+	676.520_368_121_885_1,
+	-1_259.139_216_722_402_8,
+	771.323_428_777_653_1,
+	-176.615_029_162_140_6,
+	12.507_343_278_686_905,
+	-0.138_571_095_265_720_12,
+	9.984_369_578_019_572e-6,
+	1.505_632_735_149_311_6e-7,
+];
+
+fn is_pole(x: f64) -> bool {
+	x <= 0.0 && x.fract() == 0.0
+}
+
+//Gamma of a real number, which must not be a pole
+fn gamma_real(x: f64) -> f64 {
+	if (1.0..=171.0).contains(&x) && x.fract() == 0.0 {
+		//Whole numbers are a plain product, so they come out exact
+		(2..x as usize).fold(1.0, |product, i| product * i as f64)
+	} else if x < 0.5 {
+		//The reflection formula, since the approximation only holds for the right half-plane
+		PI / ((PI * x).sin() * gamma_real(1.0 - x))
+	} else {
+		let x = x - 1.0;
+		let t = x + LANCZOS_G + 0.5;
+		let sum = (1..9).fold(LANCZOS_COEFFICIENTS[0], |sum, i| sum + LANCZOS_COEFFICIENTS[i] / (x + i as f64));
+		//The power is taken in halves so it doesn't overflow before the exponential shrinks it
+		let half_power = t.powf((x + 0.5) / 2.0);
+		(2.0 * PI).sqrt() * half_power * ((-t).exp() * half_power) * sum
+	}
+}
+
+fn gamma_complex(z: Complex64) -> Complex64 {
+	if z.re < 0.5 {
+		Complex::new(PI, 0.0) / ((z * PI).sin() * gamma_complex(Complex::new(1.0, 0.0) - z))
+	} else {
+		let z = z - 1.0;
+		let t = z + LANCZOS_G + 0.5;
+		let sum = (1..9).fold(Complex::new(LANCZOS_COEFFICIENTS[0], 0.0), |sum, i| sum + LANCZOS_COEFFICIENTS[i] / (z + i as f64));
+		let half_power = t.powc((z + 0.5) / 2.0);
+		half_power * ((-t).exp() * half_power) * sum * (2.0 * PI).sqrt()
+	}
+}
+
+//One over gamma, which is 0 at the poles
+fn reciprocal_gamma_real(x: f64) -> f64 {
+	if is_pole(x) { 0.0 } else { 1.0 / gamma_real(x) }
+}
+
+fn reciprocal_gamma_complex(z: Complex64) -> Complex64 {
+	if z.im == 0.0 && is_pole(z.re) {
+		Complex::new(0.0, 0.0)
+	} else {
+		Complex::new(1.0, 0.0) / gamma_complex(z)
+	}
+}
+
+fn undefined() -> AplError {
+	AplError::domain("factorial is undefined for negative integers")
+}
+
+//Results too large for a float come out infinite or NaN, which is an error rather than a value
+fn finite(value: Value) -> Result<Box<Value>, AplError> {
+	let is_finite = match value {
+		Value::AplFloat(f) => f.is_finite(),
+		Value::AplComplex(c) => c.is_finite(),
+		_ => true
+	};
+	if is_finite {
+		Ok(Box::new(value))
+	} else {
+		Err(AplError::domain("the result is too large"))
+	}
+}
+
+//Factorial is gamma of X+1, exact for integers until they grow too large and carry on as floats
+pub fn factorial(first: &Value) -> Result<Box<Value>, AplError> {
+	match first {
+		&Value::AplInteger(val) => {
+			if val < 0 {
+				return Err(undefined());
+			}
+			let product = (2..=val).try_fold(1isize, |product, i| product.checked_mul(i));
+			match product {
+				Some(product) => Ok(Box::new(Value::AplInteger(product))),
+				None => finite(Value::AplFloat(gamma_real(val as f64 + 1.0)))
+			}
+		},
+		&Value::AplFloat(val) => {
+			if is_pole(val + 1.0) {
+				Err(undefined())
+			} else {
+				finite(Value::AplFloat(gamma_real(val + 1.0)))
+			}
+		},
+		&Value::AplComplex(c) => {
+			if c.im == 0.0 && is_pole(c.re + 1.0) {
+				Err(undefined())
+			} else {
+				finite(Value::AplComplex(gamma_complex(c + 1.0)))
+			}
+		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
+		Value::AplArray(_dimensions, _values, _) => {
+			simple_monadic_array(factorial, first)
+		}
+	}
+}
+
+//B choose K for 0 ≤ K ≤ B, in integers while it fits and then in floats
+fn choose(n: i128, k: i128) -> Value {
+	let k = k.min(n - k);
+	let mut exact: i128 = 1;
+	let mut i = 0;
+	while i < k {
+		exact = exact * (n - i) / (i + 1);
+		i += 1;
+		if exact > isize::MAX as i128 {
+			break;
+		}
+	}
+	if i == k && exact <= isize::MAX as i128 {
+		return Value::AplInteger(exact as isize);
+	}
+	let mut approximate = exact as f64;
+	while i < k && approximate.is_finite() {
+		approximate = approximate * (n - i) as f64 / (i + 1) as f64;
+		i += 1;
+	}
+	Value::AplFloat(approximate)
+}
+
+fn negate_if(odd: bool, value: Value) -> Value {
+	match value {
+		Value::AplInteger(i) if odd => Value::AplInteger(-i),
+		Value::AplFloat(f) if odd => Value::AplFloat(-f),
+		_ => value
+	}
+}
+
+//The APL2 rules for whole numbers, where the gamma function has poles. Depending on the
+//signs of A, B and B-A the result is 0 or, up to sign, an ordinary binomial coefficient.
+fn integer_binomial(a: i128, b: i128) -> Value {
+	match (a >= 0, b >= 0, b - a >= 0) {
+		(true, true, true) => choose(b, a),
+		(true, false, false) => negate_if(a % 2 != 0, choose(a - b - 1, a)),
+		(false, false, true) => negate_if((b - a) % 2 != 0, choose(-(a + 1), -(b + 1))),
+		_ => Value::AplInteger(0)
+	}
+}
+
+fn real_binomial(a: f64, b: f64) -> Result<Value, AplError> {
+	if a.fract() == 0.0 && b.fract() == 0.0 && a.abs() < 1e18 && b.abs() < 1e18 {
+		return Ok(match integer_binomial(a as i128, b as i128) {
+			Value::AplInteger(i) => Value::AplFloat(i as f64),
+			value => value
+		});
+	}
+	if is_pole(b + 1.0) {
+		return Err(AplError::domain("the binomial is infinite here"));
+	}
+	finite(Value::AplFloat(gamma_real(b + 1.0) * reciprocal_gamma_real(a + 1.0) * reciprocal_gamma_real(b - a + 1.0))).map(|value| *value)
+}
+
+fn complex_binomial(a: Complex64, b: Complex64) -> Result<Value, AplError> {
+	let numerator = b + 1.0;
+	if numerator.im == 0.0 && is_pole(numerator.re) {
+		return Err(AplError::domain("the binomial is infinite here"));
+	}
+	finite(Value::AplComplex(gamma_complex(numerator) * reciprocal_gamma_complex(a + 1.0) * reciprocal_gamma_complex(b - a + 1.0))).map(|value| *value)
+}
+
+fn scalar_binomial(left: &Value, right: &Value) -> Result<Box<Value>, AplError> {
+	let result = match (left, right) {
+		(&Value::AplInteger(a), &Value::AplInteger(b)) => integer_binomial(a as i128, b as i128),
+		(Value::AplComplex(_), _) | (_, Value::AplComplex(_)) => complex_binomial(number(left)?, number(right)?)?,
+		_ => real_binomial(number(left)?.re, number(right)?.re)?
+	};
+	finite(result)
+}
+
+//A!B is the number of ways of choosing A things from B, generalised through the gamma function
+pub fn binomial(first: &Value, other: &Value) -> Result<Box<Value>, AplError> {
+	pervasive_dyadic(&scalar_binomial, first, other)
+}
@@ -0,0 +1,167 @@
+use std::cmp::Ordering;
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::ravel_list,
+	},
+};
+
+//Whether an item can be graded with the others: all must be real numbers or all characters
+fn check_scalars(items: &[&Value]) -> Result<(), AplError> {
+	let mut characters: Option<bool> = None;
+	for item in items.iter() {
+		let character = match item {
+			Value::AplInteger(_) | Value::AplFloat(_) => false,
+			Value::AplChar(_) => true,
+			Value::AplComplex(_) => return Err(AplError::domain("complex numbers cannot be graded")),
+			Value::AplArray(_, _, _) => return Err(AplError::domain("only simple arrays can be graded"))
+		};
+		if *characters.get_or_insert(character) != character {
+			return Err(AplError::domain("numbers and characters cannot be graded together"))
+		}
+	}
+	Ok(())
+}
+
+//Simple scalars in the order grade puts them. Reals are compared exactly and characters by
+//their code points. The sort needs a total order, so anything else, which check_scalars has
+//already refused, goes after them.
+fn compare_scalars(left: &Value, right: &Value) -> Ordering {
+	match (left, right) {
+		(&Value::AplInteger(l), &Value::AplInteger(r)) => l.cmp(&r),
+		(&Value::AplInteger(l), &Value::AplFloat(r)) => (l as f64).total_cmp(&r),
+		(&Value::AplFloat(l), &Value::AplInteger(r)) => l.total_cmp(&(r as f64)),
+		(&Value::AplFloat(l), &Value::AplFloat(r)) => l.total_cmp(&r),
+		(&Value::AplChar(l), &Value::AplChar(r)) => l.cmp(&r),
+		_ => kind(left).cmp(&kind(right))
+	}
+}
+
+fn kind(value: &Value) -> usize {
+	match value {
+		Value::AplInteger(_) | Value::AplFloat(_) => 0,
+		Value::AplChar(_) => 1,
+		Value::AplComplex(_) => 2,
+		Value::AplArray(_, _, _) => 3
+	}
+}
+
+//The major cells of an argument, the items along its first axis, each as a list of scalars
+fn major_cells(first: &Value) -> Result<Vec<&[Box<Value>]>, AplError> {
+	match first {
+		Value::AplArray(dimensions, values, _) if !dimensions.is_empty() => {
+			let cell_size: usize = dimensions[1..].iter().product();
+			Ok((0..dimensions[0]).map(|cell| &values[cell * cell_size..(cell + 1) * cell_size]).collect())
+		},
+		_ => Err(AplError::rank("cannot grade a scalar"))
+	}
+}
+
+//Sorts the indices of the cells, keeping equal cells in their original order
+fn grade_by<F>(count: usize, origin: isize, descending: bool, compare: F) -> Box<Value> where F: Fn(usize, usize) -> Ordering {
+	let mut indices: Vec<usize> = (0..count).collect();
+	indices.sort_by(|&l, &r| {
+		let order = compare(l, r);
+		if descending { order.reverse() } else { order }
+	});
+	let contents: Vec<Box<Value>> = indices.into_iter().map(|i| Box::new(Value::AplInteger(i as isize + origin))).collect();
+	Box::new(Value::AplArray(vec![count], contents, None))
+}
+
+//Cells are compared item by item in row-major order, so the rows of a matrix are ordered
+//lexicographically
+fn grade(first: &Value, origin: isize, descending: bool) -> Result<Box<Value>, AplError> {
+	let cells = major_cells(first)?;
+	let items: Vec<&Value> = cells.iter().flat_map(|cell| cell.iter()).map(|item| item.as_ref()).collect();
+	check_scalars(&items)?;
+	Ok(grade_by(cells.len(), origin, descending, |l, r| {
+		for (left, right) in cells[l].iter().zip(cells[r].iter()) {
+			match compare_scalars(left, right) {
+				Ordering::Equal => continue,
+				order => return order
+			}
+		}
+		Ordering::Equal
+	}))
+}
+
+pub fn grade_up(first: &Value, origin: isize) -> Result<Box<Value>, AplError> {
+	grade(first, origin, false)
+}
+
+pub fn grade_down(first: &Value, origin: isize) -> Result<Box<Value>, AplError> {
+	grade(first, origin, true)
+}
+
+//A character's place in a collating sequence: the coordinates of its first occurrence,
+//or past the end of every axis when it doesn't occur at all
+fn collation_keys(sequence: &Value) -> Result<Vec<(char, Vec<usize>)>, AplError> {
+	let shape = match sequence.shape() {
+		shape if shape.is_empty() => vec![1],
+		shape => shape
+	};
+	let mut keys: Vec<(char, Vec<usize>)> = vec![];
+	for (position, item) in ravel_list(sequence).iter().enumerate() {
+		let character = match **item {
+			Value::AplChar(c) => c,
+			_ => return Err(AplError::domain("a collating sequence must be characters"))
+		};
+		if keys.iter().any(|(c, _)| *c == character) {
+			continue;
+		}
+		let mut coordinates: Vec<usize> = vec![0; shape.len()];
+		let mut remainder = position;
+		for axis in (0..shape.len()).rev() {
+			coordinates[axis] = remainder % shape[axis];
+			remainder /= shape[axis];
+		}
+		keys.push((character, coordinates));
+	}
+	Ok(keys)
+}
+
+//A⍋B grades characters by their places in the collating sequence A. Cells are compared
+//first by the last axis of A alone, ties being broken by each earlier axis in turn, so a
+//matrix of upper and lower case letters sorts alphabetically before it sorts by case.
+fn collated_grade(sequence: &Value, other: &Value, origin: isize, descending: bool) -> Result<Box<Value>, AplError> {
+	let keys = collation_keys(sequence)?;
+	let rank = sequence.rank().max(1);
+	let missing = match sequence.shape() {
+		shape if shape.is_empty() => vec![1],
+		shape => shape
+	};
+
+	let cells = major_cells(other)?;
+	let mut cell_keys: Vec<Vec<&Vec<usize>>> = Vec::with_capacity(cells.len());
+	for cell in cells.iter() {
+		let mut places: Vec<&Vec<usize>> = Vec::with_capacity(cell.len());
+		for item in cell.iter() {
+			match **item {
+				Value::AplChar(c) => places.push(keys.iter().find(|(k, _)| *k == c).map(|(_, place)| place).unwrap_or(&missing)),
+				_ => return Err(AplError::domain("only characters can be graded by a collating sequence"))
+			}
+		}
+		cell_keys.push(places);
+	}
+
+	Ok(grade_by(cells.len(), origin, descending, |l, r| {
+		for axis in (0..rank).rev() {
+			for (left, right) in cell_keys[l].iter().zip(cell_keys[r].iter()) {
+				match left[axis].cmp(&right[axis]) {
+					Ordering::Equal => continue,
+					order => return order
+				}
+			}
+		}
+		Ordering::Equal
+	}))
+}
+
+pub fn collated_grade_up(first: &Value, other: &Value, origin: isize) -> Result<Box<Value>, AplError> {
+	collated_grade(first, other, origin, false)
+}
+
+pub fn collated_grade_down(first: &Value, other: &Value, origin: isize) -> Result<Box<Value>, AplError> {
+	collated_grade(first, other, origin, true)
+}
@@ -0,0 +1,42 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			array_size,
+			integer_vector,
+			make_array,
+		},
+	},
+};
+
+pub fn index_generator(first: &Value, origin: isize) -> Result<Box<Value>, AplError> {
+	let mut dimensions: Vec<usize> = vec![];
+	for dimension in integer_vector(first)? {
+		if dimension < 0 {
+			return Err(AplError::domain("negative index count"))
+		}
+		dimensions.push(dimension as usize);
+	}
+
+	let size = array_size(&dimensions)?;
+	let simple = !matches!(first, Value::AplArray(_, _, _)) || dimensions.len() == 1;
+	if simple {
+		let contents = (0..dimensions[0] as isize).map(|i| Box::new(Value::AplInteger(i + origin))).collect();
+		return Ok(Box::new(Value::AplArray(vec![dimensions[0]], contents, None)))
+	}
+
+	//A vector argument gives an array of index vectors, one for each position in that shape
+	let mut contents: Vec<Box<Value>> = Vec::with_capacity(size);
+	for position in 0..size {
+		let mut index: Vec<Box<Value>> = vec![];
+		let mut remainder = position;
+		for dimension in dimensions.iter().rev() {
+			index.push(Box::new(Value::AplInteger((remainder % dimension) as isize + origin)));
+			remainder /= dimension;
+		}
+		index.reverse();
+		contents.push(Box::new(Value::AplArray(vec![index.len()], index, None)));
+	}
+	Ok(make_array(dimensions, contents))
+}
@@ -0,0 +1,26 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			make_array,
+			ravel_list,
+			values_match,
+		},
+	},
+};
+
+//A⍳B finds the first place in A of each item of B, comparing items as = does, within ⎕CT
+pub fn index_of(first: &Value, other: &Value, origin: isize, tolerance: f64) -> Result<Box<Value>, AplError> {
+	if first.rank() != 1 {
+		return Err(AplError::rank(""))
+	}
+	let haystack = ravel_list(first);
+
+	//Items not found give the index one past the end
+	let contents: Vec<Box<Value>> = ravel_list(other).iter().map(|needle| {
+		let position = haystack.iter().position(|item| values_match(item, needle, tolerance)).unwrap_or(haystack.len());
+		Box::new(Value::AplInteger(position as isize + origin))
+	}).collect();
+	Ok(make_array(other.shape(), contents))
+}
@@ -0,0 +1,105 @@
+use crate::{
+	error::AplError,
+	nodes::Function,
+	eval::{
+		environment::Environment,
+		eval::Value,
+		array_helpers::{
+			array_size,
+			make_array,
+			ravel_list,
+		},
+	},
+};
+
+//Combines the last axis of the left argument with the first axis of the right using
+//product, then reduces each of those vectors with reduction, as in +.× for matrices
+pub fn inner_product(reduction: &Function, product: &Function, env: &mut Environment, first: &Value, other: &Value) -> Result<Box<Value>, AplError> {
+	let left_shape = first.shape();
+	let right_shape = other.shape();
+	let left_length = left_shape.last().copied();
+	let right_length = right_shape.first().copied();
+
+	//A scalar argument is extended along the shared axis
+	let length = match (left_length, right_length) {
+		(Some(l), Some(r)) if l != r => return Err(AplError::length("")),
+		(Some(l), _) => l,
+		(None, Some(r)) => r,
+		(None, None) => 1
+	};
+	let left_values = ravel_list(first);
+	let right_values = ravel_list(other);
+	let rows: usize = left_shape.iter().take(left_shape.len().saturating_sub(1)).product();
+	let columns: usize = right_shape.iter().skip(1).product();
+	let left_item = |row: usize, k: usize| if left_length.is_some() { &left_values[row * length + k] } else { &left_values[0] };
+	let right_item = |k: usize, column: usize| if right_length.is_some() { &right_values[k * columns + column] } else { &right_values[0] };
+
+	let mut dimensions: Vec<usize> = left_shape.iter().take(left_shape.len().saturating_sub(1)).copied().collect();
+	dimensions.extend(right_shape.iter().skip(1));
+	array_size(&dimensions)?;
+
+	if reduction.is_primitive("+") && product.is_primitive("×") &&
+		let Some(result_values) = numeric_matrix_product(rows, columns, length, &left_item, &right_item) {
+		return Ok(make_array(dimensions, result_values.into_iter().map(Box::new).collect()));
+	}
+
+	let mut result_values: Vec<Box<Value>> = Vec::with_capacity(rows * columns);
+	for row in 0..rows {
+		for column in 0..columns {
+			if length == 0 {
+				match reduction.identity() {
+					Some(value) => result_values.push(Box::new(value)),
+					None => return Err(AplError::domain("no identity for reduction of an empty array"))
+				}
+				continue;
+			}
+			let mut accumulator = product.apply_dyadic(env, left_item(row, length - 1), right_item(length - 1, column))?;
+			for k in (0..length - 1).rev() {
+				let item = product.apply_dyadic(env, left_item(row, k), right_item(k, column))?;
+				accumulator = reduction.apply_dyadic(env, &item, &accumulator)?;
+			}
+			result_values.push(accumulator);
+		}
+	}
+	Ok(make_array(dimensions, result_values))
+}
+
+//+.× over simple real numbers, worked out without boxing every intermediate value.
+//Gives None when an item is not a simple real or integer arithmetic would overflow.
+fn numeric_matrix_product<'a, L, R>(rows: usize, columns: usize, length: usize, left_item: &L, right_item: &R) -> Option<Vec<Value>> where L: Fn(usize, usize) -> &'a Box<Value>, R: Fn(usize, usize) -> &'a Box<Value> {
+	let all_integers = (0..rows).all(|row| (0..length).all(|k| matches!(left_item(row, k).as_ref(), Value::AplInteger(_)))) &&
+		(0..length).all(|k| (0..columns).all(|column| matches!(right_item(k, column).as_ref(), Value::AplInteger(_))));
+
+	let mut result_values: Vec<Value> = Vec::with_capacity(rows * columns);
+	for row in 0..rows {
+		for column in 0..columns {
+			if all_integers {
+				let mut sum: isize = 0;
+				for k in 0..length {
+					match (left_item(row, k).as_ref(), right_item(k, column).as_ref()) {
+						(&Value::AplInteger(l), &Value::AplInteger(r)) => {
+							sum = l.checked_mul(r).and_then(|p| sum.checked_add(p))?;
+						},
+						_ => return None
+					}
+				}
+				result_values.push(Value::AplInteger(sum));
+			} else {
+				let mut sum: f64 = 0.0;
+				for k in 0..length {
+					sum += real(left_item(row, k))? * real(right_item(k, column))?;
+				}
+				result_values.push(Value::AplFloat(sum));
+			}
+		}
+	}
+	Some(result_values)
+}
+
+fn real(value: &Value) -> Option<f64> {
+	match *value {
+		Value::AplInteger(i) => Some(i as f64),
+		Value::AplFloat(f) => Some(f),
+		_ => None
+	}
+}
@@ -0,0 +1,78 @@
+use std::f64::consts::PI;
+use num::complex::{
+	Complex,
+	Complex64,
+};
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			pervasive_dyadic,
+			simple_monadic_array,
+		},
+	},
+};
+
+//The principal natural logarithm. A negative real has a complex logarithm, which is only
+//given when complex results are allowed.
+fn scalar_log(value: &Value, complex_results: bool) -> Result<Value, AplError> {
+	let real = match *value {
+		Value::AplInteger(i) => i as f64,
+		Value::AplFloat(f) => f,
+		Value::AplComplex(c) => {
+			if c.norm() == 0.0 {
+				return Err(AplError::domain("cannot take the logarithm of 0"));
+			}
+			return Ok(Value::AplComplex(c.ln()));
+		},
+		_ => return Err(AplError::domain("expected a number"))
+	};
+	if real == 0.0 {
+		Err(AplError::domain("cannot take the logarithm of 0"))
+	} else if real > 0.0 {
+		Ok(Value::AplFloat(real.ln()))
+	} else if complex_results {
+		Ok(Value::AplComplex(Complex::new((-real).ln(), PI)))
+	} else {
+		Err(AplError::domain("cannot take the logarithm of a negative number"))
+	}
+}
+
+fn as_complex(value: &Value) -> Complex64 {
+	match *value {
+		Value::AplFloat(f) => Complex::new(f, 0.0),
+		Value::AplComplex(c) => c,
+		_ => unreachable!("logarithms are always floats or complex")
+	}
+}
+
+pub fn natural_log(first: &Value, complex_results: bool) -> Result<Box<Value>, AplError> {
+	match first {
+		Value::AplArray(_dimensions, _values, _) => {
+			simple_monadic_array(|value| natural_log(value, complex_results), first)
+		},
+		_ => scalar_log(first, complex_results).map(Box::new)
+	}
+}
+
+//B⍟X is the logarithm of X divided by the logarithm of the base B. Base 1 only reaches 1,
+//so 1⍟1 is taken to be 1 and 1⍟X fails for any other X.
+fn scalar_logarithm(base: &Value, value: &Value, complex_results: bool) -> Result<Box<Value>, AplError> {
+	let numerator = scalar_log(value, complex_results)?;
+	let denominator = scalar_log(base, complex_results)?;
+	if as_complex(&denominator).norm() == 0.0 {
+		return match as_complex(&numerator).norm() {
+			0.0 => Ok(Box::new(Value::AplFloat(1.0))),
+			_ => Err(AplError::domain("cannot take a logarithm to base 1"))
+		}
+	}
+	match (&numerator, &denominator) {
+		(&Value::AplFloat(n), &Value::AplFloat(d)) => Ok(Box::new(Value::AplFloat(n / d))),
+		_ => Ok(Box::new(Value::AplComplex(as_complex(&numerator) / as_complex(&denominator))))
+	}
+}
+
+pub fn logarithm(first: &Value, other: &Value, complex_results: bool) -> Result<Box<Value>, AplError> {
+	pervasive_dyadic(&|base: &Value, value: &Value| scalar_logarithm(base, value, complex_results), first, other)
+}
@@ -0,0 +1,142 @@
+use num::complex::{
+	Complex,
+	Complex64,
+};
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			integer_or_float,
+			number,
+			pervasive_dyadic,
+			simple_monadic_array,
+		},
+	},
+};
+
+//Reads 0 or 1, whether held as an integer or a float
+fn boolean(value: &Value) -> Result<bool, AplError> {
+	match *value {
+		Value::AplInteger(0) => Ok(false),
+		Value::AplInteger(1) => Ok(true),
+		Value::AplFloat(0.0) => Ok(false),
+		Value::AplFloat(1.0) => Ok(true),
+		_ => Err(AplError::domain("expected a boolean"))
+	}
+}
+
+fn from_boolean(truth: bool) -> Box<Value> {
+	Box::new(Value::AplInteger(truth as isize))
+}
+
+fn gcd_integer(a: isize, b: isize) -> usize {
+	let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+	while b != 0 {
+		(a, b) = (b, a % b);
+	}
+	a
+}
+
+//Euclid's algorithm, stopping once the remainder is within tolerance of zero
+fn gcd_float(a: f64, b: f64, tolerance: f64) -> f64 {
+	let threshold = tolerance * a.abs().max(b.abs());
+	let (mut a, mut b) = (a.abs(), b.abs());
+	while b > threshold {
+		(a, b) = (b, a % b);
+	}
+	a
+}
+
+//Euclid's algorithm over the Gaussian integers, rounding each quotient to the nearest one.
+//The result is the associate with a positive real part and a non-negative imaginary part.
+fn gcd_complex(a: Complex64, b: Complex64, tolerance: f64) -> Complex64 {
+	let threshold = tolerance * a.norm().max(b.norm());
+	let (mut a, mut b) = (a, b);
+	while b.norm() > threshold {
+		let quotient = a / b;
+		let quotient = Complex::new(quotient.re.round(), quotient.im.round());
+		(a, b) = (b, a - quotient * b);
+	}
+	while !(a.re > 0.0 && a.im >= 0.0) && a.norm() > 0.0 {
+		a *= Complex::new(0.0, 1.0);
+	}
+	a
+}
+
+fn scalar_gcd(left: &Value, right: &Value, tolerance: f64) -> Result<Box<Value>, AplError> {
+	match (left, right) {
+		(&Value::AplInteger(l), &Value::AplInteger(r)) => {
+			let gcd = gcd_integer(l, r);
+			match isize::try_from(gcd) {
+				Ok(gcd) => Ok(Box::new(Value::AplInteger(gcd))),
+				Err(_) => Ok(Box::new(Value::AplFloat(gcd as f64)))
+			}
+		},
+		(Value::AplComplex(_), _) | (_, Value::AplComplex(_)) => {
+			Ok(Box::new(Value::AplComplex(gcd_complex(number(left)?, number(right)?, tolerance))))
+		},
+		_ => {
+			Ok(Box::new(Value::AplFloat(gcd_float(number(left)?.re, number(right)?.re, tolerance))))
+		}
+	}
+}
+
+//The least common multiple is the product divided by the greatest common divisor, so it
+//takes the sign of the product
+fn scalar_lcm(left: &Value, right: &Value, tolerance: f64) -> Result<Box<Value>, AplError> {
+	//Zero is settled first, as the product would otherwise give -0 with a negative number
+	let (l, r) = (number(left)?, number(right)?);
+	if l.norm() == 0.0 || r.norm() == 0.0 {
+		return Ok(Box::new(Value::AplInteger(0)));
+	}
+	match (left, right) {
+		(&Value::AplInteger(l), &Value::AplInteger(r)) => {
+			let gcd = gcd_integer(l, r);
+			let exact = isize::try_from(gcd).ok().and_then(|gcd| (l / gcd).checked_mul(r));
+			Ok(integer_or_float(exact, || l as f64 / gcd as f64 * r as f64))
+		},
+		(Value::AplComplex(_), _) | (_, Value::AplComplex(_)) => {
+			let (l, r) = (number(left)?, number(right)?);
+			Ok(Box::new(Value::AplComplex(l * r / gcd_complex(l, r, tolerance))))
+		},
+		_ => {
+			let (l, r) = (number(left)?.re, number(right)?.re);
+			Ok(Box::new(Value::AplFloat(l * r / gcd_float(l, r, tolerance))))
+		}
+	}
+}
+
+//On booleans ∨ is or and ∧ is and; on other numbers they are the greatest common divisor
+//and least common multiple
+pub fn or(first: &Value, other: &Value, tolerance: f64) -> Result<Box<Value>, AplError> {
+	pervasive_dyadic(&|left: &Value, right: &Value| scalar_gcd(left, right, tolerance), first, other)
+}
+
+pub fn and(first: &Value, other: &Value, tolerance: f64) -> Result<Box<Value>, AplError> {
+	pervasive_dyadic(&|left: &Value, right: &Value| scalar_lcm(left, right, tolerance), first, other)
+}
+
+//Both sides are checked, so a non-boolean never slips through by short-circuiting
+fn boolean_pair(left: &Value, right: &Value) -> Result<(bool, bool), AplError> {
+	Ok((boolean(left)?, boolean(right)?))
+}
+
+pub fn nor(first: &Value, other: &Value) -> Result<Box<Value>, AplError> {
+	pervasive_dyadic(&|left: &Value, right: &Value| {
+		boolean_pair(left, right).map(|(l, r)| from_boolean(!(l || r)))
+	}, first, other)
+}
+
+pub fn nand(first: &Value, other: &Value) -> Result<Box<Value>, AplError> {
+	pervasive_dyadic(&|left: &Value, right: &Value| {
+		boolean_pair(left, right).map(|(l, r)| from_boolean(!(l && r)))
+	}, first, other)
+}
+
+pub fn not(first: &Value) -> Result<Box<Value>, AplError> {
+	match first {
+		Value::AplArray(_, _, _) => simple_monadic_array(not, first),
+		_ => Ok(from_boolean(!boolean(first)?))
+	}
+}
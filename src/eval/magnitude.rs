@@ -1,21 +1,21 @@
 use crate::{
-	nodes::Node,
+	error::AplError,
 	eval::{
-		eval::{
-			Value,
-			eval_monadic,
+		eval::Value,
+		array_helpers::{
+			integer_or_float,
+			simple_monadic_array,
 		},
-		array_helpers::simple_monadic_array,
 	},
 };
 
-pub fn magnitude(first: &Value) -> Result<Box<Value>, String> {
+pub fn magnitude(first: &Value) -> Result<Box<Value>, AplError> {
 	match first {
 		&Value::AplFloat(val) => {
 			Ok(Box::new(Value::AplFloat(val.abs())))
 		},
 		&Value::AplInteger(val) => {
-			Ok(Box::new(Value::AplInteger(val.abs())))
+			Ok(integer_or_float(val.checked_abs(), || (val as f64).abs()))
 		},
 		&Value::AplComplex(c) => {
 			let ii = c.re * c.re;
@@ -23,12 +23,11 @@ pub fn magnitude(first: &Value) -> Result<Box<Value>, String> {
 			let iijj = ii + jj;
 			Ok(Box::new(Value::AplFloat(iijj.sqrt())))
 		},
-		Value::AplArray(_depth, _dimensions, _values) => {
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
+		Value::AplArray(_dimensions, _values, _) => {
 			simple_monadic_array(magnitude, first)
 		}
 	}
 }
-
-pub fn eval_magnitude(left: &Node) -> Result<Box<Value>, String> {
-	eval_monadic(magnitude, left)
-}
@@ -1,10 +1,7 @@
 use crate::{
-	nodes::Node,
+	error::AplError,
 	eval::{
-		eval::{
-			Value,
-			eval_dyadic,
-		},
+		eval::Value,
 		array_helpers::{
 			simple_dyadic_array,
 			dual_dyadic_array,
@@ -13,7 +10,7 @@ use crate::{
 	},
 };
 
-fn maximum_float(f: f64, other:&Value) -> Result<Box<Value>, String> {
+fn maximum_float(f: f64, other:&Value) -> Result<Box<Value>, AplError> {
 	match *other {
 		Value::AplFloat(val) => {
 			Ok(Box::new(Value::AplFloat(if f > val { f } else { val })))
@@ -22,7 +19,10 @@ fn maximum_float(f: f64, other:&Value) -> Result<Box<Value>, String> {
 			Ok(if f > val as f64 { Box::new(Value::AplFloat(f)) } else { Box::new(Value::AplInteger(val)) })
 		},
 		Value::AplComplex(_c) => {
-			Err("Maximum is not supported on complex numbers".to_string())
+			Err(AplError::domain("maximum is not supported on complex numbers"))
+		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
 		},
 		Value::AplArray(_, _, _) => {
 			simple_dyadic_array(maximum_float, f, other)
@@ -30,7 +30,7 @@ fn maximum_float(f: f64, other:&Value) -> Result<Box<Value>, String> {
 	}
 }
 
-fn maximum_integer(i: isize, other:&Value) -> Result<Box<Value>, String> {
+fn maximum_integer(i: isize, other:&Value) -> Result<Box<Value>, AplError> {
 	match *other {
 		Value::AplFloat(val) => {
 			Ok(if i as f64 > val { Box::new(Value::AplInteger(i)) } else { Box::new(Value::AplFloat(val)) })
@@ -39,7 +39,10 @@ fn maximum_integer(i: isize, other:&Value) -> Result<Box<Value>, String> {
 			Ok(Box::new(Value::AplInteger(if i > val { i } else { val })))
 		},
 		Value::AplComplex(_c) => {
-			Err("Maximum is not supported on complex numbers".to_string())
+			Err(AplError::domain("maximum is not supported on complex numbers"))
+		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
 		},
 		Value::AplArray(_, _, _) => {
 			simple_dyadic_array(maximum_integer, i, other)
@@ -47,18 +50,21 @@ fn maximum_integer(i: isize, other:&Value) -> Result<Box<Value>, String> {
 	}
 }
 
-fn maximum_array(array: &Value, other: &Value) -> Result<Box<Value>, String> {
+fn maximum_array(array: &Value, other: &Value) -> Result<Box<Value>, AplError> {
 	match other {
 		&Value::AplFloat(_) |  &Value::AplInteger(_) | &Value::AplComplex(_) => {
 			inverse_simple_dyadic_array(maximum, array, other)
 		},
+		&Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
 		&Value::AplArray(_, _, _) => {
 			dual_dyadic_array(maximum, array, other)
 		}
 	}
 }
 
-pub fn maximum(first: &Value, other: &Value) -> Result<Box<Value>, String> {
+pub fn maximum(first: &Value, other: &Value) -> Result<Box<Value>, AplError> {
 	match first{
 		&Value::AplFloat(f) => {
 			maximum_float(f, other)
@@ -67,15 +73,14 @@ pub fn maximum(first: &Value, other: &Value) -> Result<Box<Value>, String> {
 			maximum_integer(i, other)
 		}
 		&Value::AplComplex(_c) => {
-			Err("Maximum is not supported on complex numbers".to_string())
+			Err(AplError::domain("maximum is not supported on complex numbers"))
 		},
-		Value::AplArray(_depth, _dimensions, _values) => {
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
+		Value::AplArray(_dimensions, _values, _) => {
 			maximum_array(first, other)
 		}
 	}
 }
 
-pub fn eval_maximum(left: &Node, right: &Node) -> Result<Box<Value>, String> {
-	eval_dyadic(maximum, left, right)
-}
-
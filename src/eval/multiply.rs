@@ -3,21 +3,19 @@ use num::complex::{
 	Complex64,
 };
 use crate::{
-	nodes::Node,
+	error::AplError,
 	eval::{
-		eval::{
-			Value,
-			eval_dyadic,
-		},
+		eval::Value,
 		array_helpers::{
 			simple_dyadic_array,
 			dual_dyadic_array,
+			integer_or_float,
 			inverse_simple_dyadic_array,
 		},
 	},
 };
 
-fn multiply_float(f: f64, other:&Value) -> Result<Box<Value>, String> {
+fn multiply_float(f: f64, other:&Value) -> Result<Box<Value>, AplError> {
 	match *other {
 		Value::AplFloat(val) => {
 			Ok(Box::new(Value::AplFloat(f * val)))
@@ -28,30 +26,36 @@ fn multiply_float(f: f64, other:&Value) -> Result<Box<Value>, String> {
 		Value::AplComplex(_val) => {
 			multiply_complex(&Complex::new(f, 0.0), other)
 		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
 		Value::AplArray(_, _, _) => {
 			simple_dyadic_array(multiply_float, f, other)
 		}
 	}
 }
 
-fn multiply_integer(i: isize, other:&Value) -> Result<Box<Value>, String> {
+fn multiply_integer(i: isize, other:&Value) -> Result<Box<Value>, AplError> {
 	match *other {
 		Value::AplFloat(_val) => {
 			multiply_float(i as f64, other)
 		},
 		Value::AplInteger(val) => {
-			Ok(Box::new(Value::AplInteger(i * val)))
+			Ok(integer_or_float(i.checked_mul(val), || i as f64 * val as f64))
 		},
 		Value::AplComplex(_val) => {
 			multiply_complex(&Complex::new(i as f64, 0.0), other)
 		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
 		Value::AplArray(_, _, _) => {
 			simple_dyadic_array(multiply_integer, i, other)
 		}
 	}
 }
 
-fn multiply_complex(c: &Complex64, other: &Value) -> Result<Box<Value>, String> {
+fn multiply_complex(c: &Complex64, other: &Value) -> Result<Box<Value>, AplError> {
 	match *other {
 		Value::AplFloat(f) => {
 			multiply_complex(c, &Value::AplComplex(Complex::new(f, 0.0)))
@@ -62,24 +66,30 @@ fn multiply_complex(c: &Complex64, other: &Value) -> Result<Box<Value>, String>
 		Value::AplComplex(other_c) => {
 			Ok(Box::new(Value::AplComplex(c * other_c)))
 		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
 		Value::AplArray(_, _, _) => {
 			simple_dyadic_array(multiply_complex, c, other)
 		}
 	}
 }
 
-fn multiply_array(array: &Value, other: &Value) -> Result<Box<Value>, String> {
+fn multiply_array(array: &Value, other: &Value) -> Result<Box<Value>, AplError> {
 	match other {
 		&Value::AplFloat(_) |  &Value::AplInteger(_) | &Value::AplComplex(_) => {
 			inverse_simple_dyadic_array(multiply, array, other)
 		},
+		&Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
 		&Value::AplArray(_, _, _) => {
 			dual_dyadic_array(multiply, array, other)
 		}
 	}
 }
 
-pub fn multiply(first: &Value, other: &Value) -> Result<Box<Value>, String> {
+pub fn multiply(first: &Value, other: &Value) -> Result<Box<Value>, AplError> {
 	match first{
 		&Value::AplFloat(f) => {
 			multiply_float(f, other)
@@ -90,12 +100,11 @@ pub fn multiply(first: &Value, other: &Value) -> Result<Box<Value>, String> {
 		Value::AplComplex(c) => {
 			multiply_complex(c, other)
 		},
-		Value::AplArray(_depth, _dimensions, _values) => {
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
+		Value::AplArray(_dimensions, _values, _) => {
 			multiply_array(first, other)
 		}
 	}
 }
-
-pub fn eval_multiplication(left: &Node, right: &Node) -> Result<Box<Value>, String> {
-	eval_dyadic(multiply, left, right)
-}
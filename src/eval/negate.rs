@@ -1,31 +1,30 @@
 use crate::{
-	nodes::Node,
+	error::AplError,
 	eval::{
-		eval::{
-			Value,
-			eval_monadic,
+		eval::Value,
+		array_helpers::{
+			integer_or_float,
+			simple_monadic_array,
 		},
-		array_helpers::simple_monadic_array,
 	},
 };
 
-pub fn negate(first: &Value) -> Result<Box<Value>, String> {
+pub fn negate(first: &Value) -> Result<Box<Value>, AplError> {
 	match first{
 		&Value::AplFloat(f) => {
 			Ok(Box::new(Value::AplFloat(-f)))
 		},
 		&Value::AplInteger(i) => {
-			Ok(Box::new(Value::AplInteger(-i)))
+			Ok(integer_or_float(i.checked_neg(), || -(i as f64)))
 		}
 		&Value::AplComplex(c) => {
 			Ok(Box::new(Value::AplComplex(-c)))
 		},
-		Value::AplArray(_depth, _dimensions, _values) => {
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
+		Value::AplArray(_dimensions, _values, _) => {
 			simple_monadic_array(negate, first)
 		}
 	}
 }
-
-pub fn eval_negate(left: &Node) -> Result<Box<Value>, String> {
-	eval_monadic(negate, left)
-}
@@ -0,0 +1,30 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			array_size,
+			make_array,
+			ravel_list,
+		},
+	},
+};
+
+//Applies func between every item of the left argument and every item of the right,
+//the result having the shape of both arguments joined together
+pub fn outer_product<F>(mut func: F, first: &Value, other: &Value) -> Result<Box<Value>, AplError> where F: FnMut(&Value, &Value) -> Result<Box<Value>, AplError> {
+	let mut dimensions = first.shape();
+	dimensions.extend(other.shape());
+	let size = array_size(&dimensions)?;
+
+	let left_values = ravel_list(first);
+	let right_values = ravel_list(other);
+	let mut result_values: Vec<Box<Value>> = Vec::with_capacity(size);
+	for left in left_values.iter() {
+		for right in right_values.iter() {
+			result_values.push(func(left, right)?);
+		}
+	}
+
+	Ok(make_array(dimensions, result_values))
+}
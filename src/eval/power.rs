@@ -3,84 +3,111 @@ use num::complex::{
 	Complex64
 };
 use crate::{
-	nodes::Node,
+	error::AplError,
 	eval::{
-		eval::{
-			Value,
-			eval_dyadic,
-		},
+		eval::Value,
 		array_helpers::{
 			simple_dyadic_array,
 			dual_dyadic_array,
+			integer_or_float,
 			inverse_simple_dyadic_array,
 		},
 	},
 };
 
-fn power_float(f: f64, other:&Value) -> Result<Box<Value>, String> {
+//A negative number to a fractional power has only complex roots. The principal one is given
+//when ⎕CX allows complex results, and otherwise there is no answer.
+fn negative_power(f: f64, val: f64, complex_results: bool) -> Result<Box<Value>, AplError> {
+	if complex_results {
+		power_complex(&Complex::new(f, 0.0), &Value::AplFloat(val))
+	} else {
+		Err(AplError::domain("a negative number to a fractional power is complex"))
+	}
+}
+
+fn power_float(f: f64, other:&Value, complex_results: bool) -> Result<Box<Value>, AplError> {
 	match *other {
 		Value::AplFloat(val) => {
 			if f == 0.0 && val < 0.0 {
-				Err("Cannot take 0 to a negative power".to_string()) //FIXME: Make this a constant
+				Err(AplError::domain("cannot take 0 to a negative power")) //FIXME: Make this a constant
+			} else if f < 0.0 && val.fract() != 0.0 {
+				negative_power(f, val, complex_results)
 			} else {
 				Ok(Box::new(Value::AplFloat(f.powf(val))))
 			}
 		},
 		Value::AplInteger(val) => {
 			if f == 0.0 && val < 0 {
-				Err("Cannot take 0 to a negative power".to_string())
+				Err(AplError::domain("cannot take 0 to a negative power"))
 			} else {
 				Ok(Box::new(Value::AplFloat(f.powf(val as f64))))
 			}
 		},
-		Value::AplComplex(c) => {
-			let fpow = f.powf(c.re);
-			let im_times_lnf = c.im * f.ln();
-			let real =  fpow * im_times_lnf.cos();
-			let imaginary = fpow * im_times_lnf.sin();
-			Ok(Box::new(Value::AplComplex(Complex::new(real, imaginary))))
+		Value::AplComplex(_) => {
+			power_complex(&Complex::new(f, 0.0), other)
+		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
 		},
 		Value::AplArray(_, _, _) => {
-			simple_dyadic_array(power_float, f, other)
+			simple_dyadic_array(|f, value| power_float(f, value, complex_results), f, other)
 		}
 	}
 }
 
-fn power_integer(i: isize, other:&Value) -> Result<Box<Value>, String> {
+fn power_integer(i: isize, other:&Value, complex_results: bool) -> Result<Box<Value>, AplError> {
 	match *other {
-		Value::AplFloat(val) => {
-			if i == 0 && val < 0.0 {
-				Err("Cannot take 0 to a negative power".to_string())
-			} else {
-				Ok(Box::new(Value::AplFloat((i as f64).powf(val))))
-			}
+		Value::AplFloat(_) => {
+			power_float(i as f64, other, complex_results)
 		},
 		Value::AplInteger(val) => {
 			if i == 0 && val < 0 {
-				Err("Cannot take 0 to a negative power".to_string())
+				Err(AplError::domain("cannot take 0 to a negative power"))
+			} else if val < 0 {
+				//A negative power is a fraction, so it's a float
+				Ok(Box::new(Value::AplFloat((i as f64).powf(val as f64))))
 			} else {
-				Ok(Box::new(Value::AplInteger((i as f64).powf(val as f64) as isize)))
+				let exact = u32::try_from(val).ok().and_then(|val| i.checked_pow(val));
+				Ok(integer_or_float(exact, || (i as f64).powf(val as f64)))
 			}
 		},
 		Value::AplComplex(_c) => {
-			power_float(i as f64, other)
+			power_float(i as f64, other, complex_results)
+		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
 		},
 		Value::AplArray(_, _, _) => {
-			simple_dyadic_array(power_integer, i, other)
+			simple_dyadic_array(|i, value| power_integer(i, value, complex_results), i, other)
 		}
 	}
 }
 
-fn power_complex(c: &Complex64, other:&Value) -> Result<Box<Value>, String> {
+fn power_complex(c: &Complex64, other:&Value) -> Result<Box<Value>, AplError> {
 	match *other {
 		Value::AplFloat(val) => {
 			power_complex(c, &Value::AplComplex(Complex::new(val, 0.0)))
 		},
 		Value::AplInteger(val) => {
-			power_complex(c, &Value::AplComplex(Complex::new(val as f64, 0.0)))
+			//Whole powers are repeated multiplication, which keeps 0J1*2 exactly ¯1
+			match i32::try_from(val) {
+				Ok(val) if c.norm() != 0.0 || val >= 0 => Ok(Box::new(Value::AplComplex(c.powi(val)))),
+				_ => power_complex(c, &Value::AplComplex(Complex::new(val as f64, 0.0)))
+			}
 		},
-		Value::AplComplex(_c) => {
-			Err("power is not supported on complex numbers".to_string())
+		Value::AplComplex(val) => {
+			if c.norm() != 0.0 {
+				Ok(Box::new(Value::AplComplex((val * c.ln()).exp())))
+			} else if val.re > 0.0 {
+				Ok(Box::new(Value::AplComplex(Complex::new(0.0, 0.0))))
+			} else if val.norm() == 0.0 {
+				Ok(Box::new(Value::AplComplex(Complex::new(1.0, 0.0))))
+			} else {
+				Err(AplError::domain("cannot take 0 to a negative power"))
+			}
+		},
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
 		},
 		Value::AplArray(_, _, _) => {
 			simple_dyadic_array(power_complex, c, other)
@@ -88,35 +115,37 @@ fn power_complex(c: &Complex64, other:&Value) -> Result<Box<Value>, String> {
 	}
 }
 
-fn power_array(array: &Value, other: &Value) -> Result<Box<Value>, String> {
+fn power_array(array: &Value, other: &Value, complex_results: bool) -> Result<Box<Value>, AplError> {
 	match other {
 		&Value::AplFloat(_) |  &Value::AplInteger(_) | &Value::AplComplex(_) => {
-			inverse_simple_dyadic_array(power, array, other)
+			inverse_simple_dyadic_array(|left, right| power(left, right, complex_results), array, other)
+		},
+		&Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
 		},
 		&Value::AplArray(_, _, _) => {
-			dual_dyadic_array(power, array, other)
+			dual_dyadic_array(|left, right| power(left, right, complex_results), array, other)
 		}
 	}
 }
 
-pub fn power(first: &Value, other: &Value) -> Result<Box<Value>, String> {
+pub fn power(first: &Value, other: &Value, complex_results: bool) -> Result<Box<Value>, AplError> {
 	match first{
 		&Value::AplFloat(f) => {
-			power_float(f, other)
+			power_float(f, other, complex_results)
 		},
 		&Value::AplInteger(i) => {
-			power_integer(i, other)
+			power_integer(i, other, complex_results)
 		}
 		Value::AplComplex(c) => {
 			power_complex(c, other)
 		},
-		Value::AplArray(_depth, _dimensions, _values) => {
-			power_array(first, other)
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
+		Value::AplArray(_dimensions, _values, _) => {
+			power_array(first, other, complex_results)
 		}
 	}
 }
 
-pub fn eval_power(left: &Node, right: &Node) -> Result<Box<Value>, String> {
-	eval_dyadic(power, left, right)
-}
-
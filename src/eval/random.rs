@@ -0,0 +1,131 @@
+use std::{
+	cell::Cell,
+	collections::HashMap,
+};
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		environment::Environment,
+		array_helpers::{
+			array_size,
+			integer_vector,
+			simple_monadic_array,
+		},
+	},
+};
+
+//The Park-Miller minimal standard generator that APL has traditionally used: each state
+//is the last times 16807 modulo the prime 2147483647. It is fully specified, so a given
+//⎕RL gives the same numbers on every platform.
+const MULTIPLIER: i64 = 16807;
+const MODULUS: i64 = 2147483647;
+//Each step gives one of this many values, 0 up to MODULUS-2
+const SPAN: u64 = MODULUS as u64 - 1;
+
+pub const DEFAULT_RANDOM_LINK: isize = 16807;
+
+//Whether a value can be used as ⎕RL
+pub fn is_random_link(value: isize) -> bool {
+	(1..MODULUS as isize).contains(&value)
+}
+
+pub struct RandomLink(Cell<i64>);
+
+impl RandomLink {
+	pub fn new(seed: isize) -> RandomLink {
+		RandomLink(Cell::new(seed as i64))
+	}
+
+	//The state to store back in ⎕RL
+	pub fn seed(&self) -> isize {
+		self.0.get() as isize
+	}
+
+	fn step(&self) -> u64 {
+		self.0.set(self.0.get() * MULTIPLIER % MODULUS);
+		self.0.get() as u64 - 1
+	}
+
+	//A uniform float strictly between 0 and 1
+	fn float(&self) -> f64 {
+		(self.step() + 1) as f64 / MODULUS as f64
+	}
+
+	//A uniform whole number from 0 up to bound-1. Draws past the last whole multiple of the
+	//bound are thrown away so that every result is equally likely.
+	fn below(&self, bound: u64) -> Result<u64, AplError> {
+		let (span, draw): (u64, &dyn Fn() -> u64) = if bound <= SPAN {
+			(SPAN, &|| self.step())
+		} else if bound <= SPAN * SPAN {
+			(SPAN * SPAN, &|| self.step() * SPAN + self.step())
+		} else {
+			return Err(AplError::limit("the argument is too large to roll"));
+		};
+		let limit = span - span % bound;
+		loop {
+			let value = draw();
+			if value < limit {
+				return Ok(value % bound);
+			}
+		}
+	}
+}
+
+//Runs a function with the generator held in ⎕RL, storing its new state back afterwards
+pub fn with_random_link<F>(env: &mut Environment, func: F) -> Result<Box<Value>, AplError> where F: FnOnce(&RandomLink) -> Result<Box<Value>, AplError> {
+	let link = RandomLink::new(env.random_link());
+	let result = func(&link);
+	env.set("⎕RL", Value::AplInteger(link.seed()))?;
+	result
+}
+
+//?N is a whole number chosen at random from ⍳N, and ?0 a float between 0 and 1
+pub fn roll(first: &Value, link: &RandomLink, origin: isize) -> Result<Box<Value>, AplError> {
+	match first {
+		Value::AplArray(_dimensions, _values, _) => {
+			simple_monadic_array(|value| roll(value, link, origin), first)
+		},
+		Value::AplInteger(_) | Value::AplFloat(_) => {
+			match integer_vector(first)?[0] {
+				0 => Ok(Box::new(Value::AplFloat(link.float()))),
+				bound if bound > 0 => {
+					Ok(Box::new(Value::AplInteger(link.below(bound as u64)? as isize + origin)))
+				},
+				_ => Err(AplError::domain("cannot roll a negative number"))
+			}
+		},
+		_ => Err(AplError::domain("expected an integer"))
+	}
+}
+
+fn scalar_integer(value: &Value) -> Result<isize, AplError> {
+	match integer_vector(value)?.as_slice() {
+		[i] => Ok(*i),
+		_ => Err(AplError::length("expected a single number"))
+	}
+}
+
+//A?B is A different numbers chosen at random from ⍳B, in the order they were drawn
+pub fn deal(first: &Value, other: &Value, link: &RandomLink, origin: isize) -> Result<Box<Value>, AplError> {
+	let count = scalar_integer(first)?;
+	let population = scalar_integer(other)?;
+	if count < 0 || population < 0 {
+		return Err(AplError::domain("cannot deal a negative number"));
+	}
+	if count > population {
+		return Err(AplError::domain("cannot deal more numbers than there are to choose from"));
+	}
+	array_size(&[count as usize])?;
+
+	//A shuffle of ⍳B stopped after A swaps, keeping only the positions that have moved
+	let mut moved: HashMap<isize, isize> = HashMap::new();
+	let mut contents: Vec<Box<Value>> = Vec::with_capacity(count as usize);
+	for i in 0..count {
+		let j = i + link.below((population - i) as u64)? as isize;
+		let chosen = *moved.get(&j).unwrap_or(&j);
+		moved.insert(j, *moved.get(&i).unwrap_or(&i));
+		contents.push(Box::new(Value::AplInteger(chosen + origin)));
+	}
+	Ok(Box::new(Value::AplArray(vec![contents.len()], contents, None)))
+}
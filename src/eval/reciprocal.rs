@@ -1,18 +1,11 @@
 use crate::{
-	nodes::Node,
+	error::AplError,
 	eval::{
-		eval::{
-			Value,
-			eval_monadic,
-		},
+		eval::Value,
 		divide::divide_integer,
 	},
 };
 
-pub fn reciprocal(first: &Value) -> Result<Box<Value>, String> {
+pub fn reciprocal(first: &Value) -> Result<Box<Value>, AplError> {
 	divide_integer(1, first)
 }
-
-pub fn eval_reciprocal(left: &Node) -> Result<Box<Value>, String> {
-	eval_monadic(reciprocal, left)
-}
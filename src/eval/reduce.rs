@@ -0,0 +1,47 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			axis_split,
+			make_array,
+		},
+	},
+};
+
+//Inserts func between the items along an axis, evaluating right to left
+pub fn reduce<F>(mut func: F, identity: Option<Value>, first: &Value, axis: usize) -> Result<Box<Value>, AplError> where F: FnMut(&Value, &Value) -> Result<Box<Value>, AplError> {
+	match first {
+		Value::AplArray(dimensions, values, _) if !dimensions.is_empty() => {
+			if axis >= dimensions.len() {
+				return Err(AplError::axis(""))
+			}
+			let (outer, length, inner) = axis_split(dimensions, axis);
+			let mut result_dimensions = dimensions.clone();
+			result_dimensions.remove(axis);
+
+			let mut result_values: Vec<Box<Value>> = Vec::with_capacity(outer * inner);
+			for o in 0..outer {
+				for j in 0..inner {
+					if length == 0 {
+						match identity {
+							Some(ref value) => result_values.push(Box::new(value.clone())),
+							None => return Err(AplError::domain("no identity for reduction of an empty array"))
+						}
+					} else {
+						let mut accumulator = values[(o * length + length - 1) * inner + j].clone();
+						for i in (0..length - 1).rev() {
+							accumulator = func(&values[(o * length + i) * inner + j], &accumulator)?;
+						}
+						result_values.push(accumulator);
+					}
+				}
+			}
+			Ok(make_array(result_dimensions, result_values))
+		},
+		_ => {
+			//Reducing a scalar leaves it alone
+			Ok(Box::new(first.clone()))
+		}
+	}
+}
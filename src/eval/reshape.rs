@@ -0,0 +1,34 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			array_size,
+			integer_vector,
+			filled_array,
+			prototype,
+			ravel_list,
+		},
+	},
+};
+
+pub fn reshape(first: &Value, other: &Value) -> Result<Box<Value>, AplError> {
+	let mut dimensions: Vec<usize> = vec![];
+	for dimension in integer_vector(first)? {
+		if dimension < 0 {
+			return Err(AplError::domain("negative dimension"))
+		}
+		dimensions.push(dimension as usize);
+	}
+
+	let size = array_size(&dimensions)?;
+	let fill = prototype(other);
+	let mut source = ravel_list(other);
+	if source.is_empty() {
+		source.push(Box::new(fill.clone()));
+	}
+
+	//Items are reused cyclically until the new shape is filled
+	let contents: Vec<Box<Value>> = source.iter().cycle().take(size).cloned().collect();
+	Ok(filled_array(dimensions, contents, &fill))
+}
@@ -0,0 +1,88 @@
+use num::complex::{
+	Complex,
+	Complex64,
+};
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			number,
+			pervasive_dyadic,
+		},
+	},
+};
+
+fn residue_integer(a: isize, b: isize) -> isize {
+	//isize::MIN|¯1 overflows the remainder, but any multiple of ¯1 leaves nothing
+	let remainder = b.checked_rem(a).unwrap_or(0);
+	//The result takes the sign of the left argument
+	if remainder != 0 && (remainder < 0) != (a < 0) {
+		remainder + a
+	} else {
+		remainder
+	}
+}
+
+//B-A×⌊B÷A, except that a quotient tolerantly equal to a whole number leaves 0
+fn residue_float(a: f64, b: f64, tolerance: f64) -> f64 {
+	let quotient = b / a;
+	let nearest = quotient.round();
+	if (quotient - nearest).abs() <= tolerance * quotient.abs().max(nearest.abs()) {
+		0.0
+	} else {
+		b - a * quotient.floor()
+	}
+}
+
+//The complex floor: the Gaussian integer below and to the left, moved one step right or
+//up when the fractional parts add to at least one
+fn complex_floor(c: Complex64) -> Complex64 {
+	let floor = Complex::new(c.re.floor(), c.im.floor());
+	let (x, y) = (c.re - floor.re, c.im - floor.im);
+	if x + y < 1.0 {
+		floor
+	} else if x >= y {
+		floor + Complex::new(1.0, 0.0)
+	} else {
+		floor + Complex::new(0.0, 1.0)
+	}
+}
+
+fn residue_complex(a: Complex64, b: Complex64, tolerance: f64) -> Complex64 {
+	let quotient = b / a;
+	let nearest = Complex::new(quotient.re.round(), quotient.im.round());
+	if (quotient - nearest).norm() <= tolerance * quotient.norm().max(nearest.norm()) {
+		Complex::new(0.0, 0.0)
+	} else {
+		b - a * complex_floor(quotient)
+	}
+}
+
+fn scalar_residue(left: &Value, right: &Value, tolerance: f64) -> Result<Box<Value>, AplError> {
+	let (a, b) = (number(left)?, number(right)?);
+	//0|B is B itself
+	if a.norm() == 0.0 {
+		return Ok(Box::new(right.clone()));
+	}
+	if !b.is_finite() || (!a.is_finite() && (a.im != 0.0 || b.im != 0.0)) {
+		return Err(AplError::domain("the residue of an infinite number is not defined"));
+	}
+	//As A grows, A|B stays B when B is 0 or has the sign of A, and otherwise tends to A+B
+	if !a.is_finite() {
+		if b.re == 0.0 || (b.re < 0.0) == (a.re < 0.0) {
+			return Ok(Box::new(right.clone()));
+		}
+		return Ok(Box::new(Value::AplFloat(a.re)));
+	}
+	let result = match (left, right) {
+		(&Value::AplInteger(a), &Value::AplInteger(b)) => Value::AplInteger(residue_integer(a, b)),
+		(Value::AplComplex(_), _) | (_, Value::AplComplex(_)) => Value::AplComplex(residue_complex(a, b, tolerance)),
+		_ => Value::AplFloat(residue_float(a.re, b.re, tolerance))
+	};
+	Ok(Box::new(result))
+}
+
+pub fn residue(first: &Value, other: &Value, tolerance: f64) -> Result<Box<Value>, AplError> {
+	pervasive_dyadic(&|left: &Value, right: &Value| scalar_residue(left, right, tolerance), first, other)
+}
@@ -0,0 +1,79 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			axis_split,
+			filled_array,
+			integer_vector,
+			prototype,
+		},
+	},
+};
+
+//Reverses the order of the items along an axis
+pub fn reverse(first: &Value, axis: usize) -> Result<Box<Value>, AplError> {
+	match first {
+		Value::AplArray(dimensions, values, _) if !dimensions.is_empty() => {
+			if axis >= dimensions.len() {
+				return Err(AplError::axis(""))
+			}
+			let (outer, length, inner) = axis_split(dimensions, axis);
+			let mut result_values: Vec<Box<Value>> = Vec::with_capacity(values.len());
+			for o in 0..outer {
+				for i in (0..length).rev() {
+					result_values.extend_from_slice(&values[(o * length + i) * inner..(o * length + i + 1) * inner]);
+				}
+			}
+			Ok(filled_array(dimensions.clone(), result_values, &prototype(first)))
+		},
+		_ => {
+			//Reversing a scalar leaves it alone
+			Ok(Box::new(first.clone()))
+		}
+	}
+}
+
+//A⌽B rotates each line along an axis A places towards its start, wrapping round. A is
+//either one count for every line or an array of counts shaped like B without that axis.
+pub fn rotate(first: &Value, other: &Value, axis: usize) -> Result<Box<Value>, AplError> {
+	let (dimensions, values) = match other {
+		Value::AplArray(dimensions, values, _) if !dimensions.is_empty() => (dimensions, values),
+		_ => {
+			integer_vector(first)?;
+			return Ok(Box::new(other.clone()))
+		}
+	};
+	if axis >= dimensions.len() {
+		return Err(AplError::axis(""))
+	}
+	let (outer, length, inner) = axis_split(dimensions, axis);
+
+	let counts = integer_vector(&match first {
+		Value::AplArray(_, contents, _) => Value::AplArray(vec![contents.len()], contents.clone(), None),
+		_ => first.clone()
+	})?;
+	if counts.len() != 1 {
+		let mut frame = dimensions.clone();
+		frame.remove(axis);
+		if first.rank() != frame.len() {
+			return Err(AplError::rank(""))
+		} else if first.shape() != frame {
+			return Err(AplError::length(""))
+		}
+	}
+
+	let mut result_values: Vec<Box<Value>> = Vec::with_capacity(values.len());
+	for o in 0..outer {
+		for i in 0..length {
+			for j in 0..inner {
+				let count = if counts.len() == 1 { counts[0] } else { counts[o * inner + j] };
+				//Reduced first, so that a count near the limits of an integer can't overflow
+				let shift = count.rem_euclid(length as isize) as usize;
+				let source = (i + shift) % length;
+				result_values.push(values[(o * length + source) * inner + j].clone());
+			}
+		}
+	}
+	Ok(filled_array(dimensions.clone(), result_values, &prototype(other)))
+}
@@ -0,0 +1,42 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::axis_split,
+	},
+};
+
+//Each item along an axis becomes the reduction of the items up to and including it.
+//Associative functions can carry a running total instead of reducing every prefix.
+pub fn scan<F>(mut func: F, associative: bool, first: &Value, axis: usize) -> Result<Box<Value>, AplError> where F: FnMut(&Value, &Value) -> Result<Box<Value>, AplError> {
+	match first {
+		Value::AplArray(dimensions, values, _) if !dimensions.is_empty() => {
+			if axis >= dimensions.len() {
+				return Err(AplError::axis(""))
+			}
+			let (outer, length, inner) = axis_split(dimensions, axis);
+			let mut result_values: Vec<Box<Value>> = values.clone();
+
+			for o in 0..outer {
+				for j in 0..inner {
+					let position = |i: usize| (o * length + i) * inner + j;
+					for i in 1..length {
+						result_values[position(i)] = if associative {
+							func(&result_values[position(i - 1)], &values[position(i)])?
+						} else {
+							let mut accumulator = values[position(i)].clone();
+							for k in (0..i).rev() {
+								accumulator = func(&values[position(k)], &accumulator)?;
+							}
+							accumulator
+						};
+					}
+				}
+			}
+			Ok(Box::new(Value::AplArray(dimensions.clone(), result_values, None)))
+		},
+		_ => {
+			Ok(Box::new(first.clone()))
+		}
+	}
+}
@@ -0,0 +1,11 @@
+use crate::{
+	error::AplError,
+	eval::eval::Value,
+};
+
+pub fn shape(first: &Value) -> Result<Box<Value>, AplError> {
+	let dimensions: Vec<Box<Value>> = first.shape().iter()
+		.map(|dimension| Box::new(Value::AplInteger(*dimension as isize)))
+		.collect();
+	Ok(Box::new(Value::AplArray(vec![dimensions.len()], dimensions, None)))
+}
@@ -1,17 +1,14 @@
 use crate::{
-	nodes::Node,
+	error::AplError,
 	eval::{
-		eval::{
-			Value,
-			eval_monadic,
-		},
+		eval::Value,
 		array_helpers::simple_monadic_array,
 		divide::divide,
 		magnitude::magnitude,
 	},
 };
 
-pub fn sign(first: &Value) -> Result<Box<Value>, String> {
+pub fn sign(first: &Value) -> Result<Box<Value>, AplError> {
 	match first {
 		&Value::AplFloat(val) => {
 			Ok(if val < 0.0 {
@@ -36,12 +33,11 @@ pub fn sign(first: &Value) -> Result<Box<Value>, String> {
 				divide(first, &magnituded)
 			})
 		},
-		Value::AplArray(_depth, _dimensions, _values) => {
+		Value::AplChar(_) => {
+			Err(AplError::domain("expected a number"))
+		},
+		Value::AplArray(_dimensions, _values, _) => {
 			simple_monadic_array(sign, first)
 		}
 	}
 }
-
-pub fn eval_sign(left: &Node) -> Result<Box<Value>, String> {
-	eval_monadic(sign, left)
-}
@@ -0,0 +1,55 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			integer_vector,
+			prototype,
+			ravel_list,
+			window,
+		},
+	},
+};
+
+//The counts for take or drop, one for each leading axis. A scalar argument is treated as
+//having an axis for every count.
+pub fn axis_counts(first: &Value, other: &Value) -> Result<Vec<isize>, AplError> {
+	let counts = integer_vector(first)?;
+	if other.rank() > 0 && counts.len() > other.rank() {
+		return Err(AplError::rank(""))
+	}
+	Ok(counts)
+}
+
+//The shape of the argument in a take or drop, with the axes after the counts left whole
+pub fn source_shape(other: &Value, counts: &[isize]) -> Vec<usize> {
+	match other.rank() {
+		0 => vec![1; counts.len()],
+		_ => other.shape()
+	}
+}
+
+//A↑B takes A[i] items along each axis i, from the end when the count is negative. Taking
+//more than there are pads with the fill item.
+pub fn take(first: &Value, other: &Value) -> Result<Box<Value>, AplError> {
+	let counts = axis_counts(first, other)?;
+	let shape = source_shape(other, &counts);
+
+	let mut dimensions = shape.clone();
+	let mut offsets: Vec<isize> = vec![0; shape.len()];
+	for (axis, &count) in counts.iter().enumerate() {
+		dimensions[axis] = count.unsigned_abs();
+		if count < 0 {
+			offsets[axis] = shape[axis] as isize + count;
+		}
+	}
+	window(other, dimensions, &offsets, &prototype(other))
+}
+
+//↑B is the first item of B, or its fill item when it is empty
+pub fn first(first: &Value) -> Result<Box<Value>, AplError> {
+	match ravel_list(first).into_iter().next() {
+		Some(item) => Ok(item),
+		None => Ok(Box::new(prototype(first)))
+	}
+}
@@ -0,0 +1,10 @@
+use crate::{
+	error::AplError,
+	eval::eval::Value,
+};
+
+//The number of major cells, a scalar counting as one
+pub fn tally(first: &Value) -> Result<Box<Value>, AplError> {
+	let count = first.shape().first().copied().unwrap_or(1);
+	Ok(Box::new(Value::AplInteger(count as isize)))
+}
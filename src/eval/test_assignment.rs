@@ -0,0 +1,81 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::{
+			Evaluator,
+			Printable,
+		},
+		test_eval::{
+			test_eval_display,
+			test_eval_fail,
+		},
+	},
+};
+
+#[test]
+fn test_assignment_persists() {
+	//One evaluator keeps its names from one line to the next
+	let mut eval = Evaluator::new();
+	assert!(eval.eval("X←1 2 3".to_string()).is_ok());
+	match eval.eval("X+1".to_string()) {
+		Ok(Some(result)) => assert_eq!(result.to_string(), "2 3 4"),
+		_ => panic!("X+1 - gave no value")
+	}
+	match eval.eval("X←X,4 ⋄ X".to_string()) {
+		Ok(Some(result)) => assert_eq!(result.to_string(), "1 2 3 4"),
+		_ => panic!("X←X,4 - gave no value")
+	}
+}
+
+#[test]
+fn test_assignment_value() {
+	//An assignment gives its value to the rest of the expression
+	test_eval_display("1+c←2", "3");
+	test_eval_display("1+c←2 ⋄ c", "2");
+	test_eval_display("a←b←5 ⋄ a+b", "10");
+	test_eval_display("x←'ab' ⋄ x", "ab");
+	test_eval_display("x←1 ⋄ x←2 ⋄ x", "2");
+	//Names are case sensitive
+	test_eval_display("x←1 ⋄ X←2 ⋄ x", "1");
+}
+
+#[test]
+fn test_modified_assignment() {
+	test_eval_display("x←1 2 ⋄ x+←1 ⋄ x", "2 3");
+	test_eval_display("x←1 2 ⋄ x×←2 3 ⋄ x", "2 6");
+	test_eval_display("x←1 2 ⋄ x,←3 ⋄ x", "1 2 3");
+	test_eval_fail("q+←1", |error| {
+		assert_eq!(error, AplError::value("q is not defined").at_position(1, 1));
+	});
+}
+
+#[test]
+fn test_multiple_assignment() {
+	test_eval_display("(a b)←3 4 ⋄ a", "3");
+	test_eval_display("(a b)←3 4 ⋄ b", "4");
+	//A scalar goes to every name
+	test_eval_display("(a b)←7 ⋄ a+b", "14");
+	test_eval_display("(a b)←(1 2)'cd' ⋄ b", "cd");
+	test_eval_fail("(a b)←1 2 3", |error| {
+		assert!(matches!(error, AplError::Length(_, _)));
+	});
+	test_eval_fail("(a b)←2 2⍴1", |error| {
+		assert!(matches!(error, AplError::Rank(_, _)));
+	});
+}
+
+#[test]
+fn test_assignment_errors() {
+	test_eval_fail("y", |error| {
+		assert_eq!(error, AplError::value("y is not defined").at_position(1, 1));
+	});
+	test_eval_fail("1+y", |error| {
+		assert_eq!(error, AplError::value("y is not defined").at_position(1, 3));
+	});
+	test_eval_fail("3←4", |error| {
+		assert!(matches!(error, AplError::Syntax(_, _)));
+	});
+	test_eval_fail("x←", |error| {
+		assert!(matches!(error, AplError::Syntax(_, _)));
+	});
+}
@@ -0,0 +1,54 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval,
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_catenate_vectors() {
+	test_eval_display("1 2,3 4", "1 2 3 4");
+	test_eval_display("1,2 3", "1 2 3");
+	test_eval_display("'ab','c'", "abc");
+}
+
+#[test]
+fn test_catenate_enclosed_scalar() {
+	test_eval_display("⍴(⊂1 2),2 2⍴⍳4", "2 3");
+	test_eval_display("⍴(2 2⍴⍳4)⍪⊂1 2", "3 2");
+	test_eval_display("⍴(0 2⍴0)⍪⊂1 2", "1 2");
+	test_eval_display("⍴(⊂1 2),[1]2 2 2⍴⍳8", "3 2 2");
+	test_eval_display("(⊂1 2),2 2⍴⍳4", " 1 2  1 2\n 1 2  3 4");
+	test_eval_display("2↑,(⊂1 2),2 2⍴⍳4", " 1 2  1");
+	test_eval_display("⍴¨((⊂1 2)(⊂3)),¨(2 2⍴⍳4)(1 2)", " 2 3  3");
+}
+
+#[test]
+fn test_laminate_enclosed_scalar() {
+	test_eval_display("⍴(⊂1 2),[0.5]2 2⍴⍳4", "2 2 2");
+	test_eval_display("⍴(⊂1 2),[0.5]⊂3 4", "2");
+	test_eval_display("⍴(2 2⍴⍳4),[2.5]⊂1 2", "2 2 2");
+}
+
+#[test]
+fn test_catenate_errors() {
+	test_eval_fail("1 2,2 2 2⍴⍳8", |error| {
+		assert!(matches!(error, AplError::Rank(_, _)));
+	});
+	test_eval_fail("(2 2⍴⍳4)⍪1 2 3", |error| {
+		assert!(matches!(error, AplError::Length(_, _)));
+	});
+	test_eval_fail("1 2,[3]3 4", |error| {
+		assert!(matches!(error, AplError::Axis(_, _)));
+	});
+}
+
+#[test]
+fn test_ravel() {
+	test_eval(",2 2⍴⍳4", |result| {
+		assert_eq!(result.shape(), vec![4]);
+	});
+	test_eval_display(",⊂1 2", " 1 2");
+}
@@ -0,0 +1,133 @@
+use std::f64::consts::PI;
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		test_eval::{
+			assert_approx_eq,
+			test_eval,
+			test_eval_display,
+			test_eval_fail,
+		},
+	},
+};
+
+//The real and imaginary parts of a numeric result
+fn parts(input: &str) -> (f64, f64) {
+	let mut result = (f64::NAN, f64::NAN);
+	test_eval(input, |value| {
+		result = match *value {
+			Value::AplInteger(i) => (i as f64, 0.0),
+			Value::AplFloat(f) => (f, 0.0),
+			Value::AplComplex(c) => (c.re, c.im),
+			_ => panic!("{} - didn't give a number", input)
+		};
+	});
+	result
+}
+
+fn assert_circle(input: &str, re: f64, im: f64) {
+	let (actual_re, actual_im) = parts(input);
+	assert_approx_eq(actual_re, re);
+	assert_approx_eq(actual_im, im);
+}
+
+#[test]
+fn test_pi_times() {
+	assert_circle("○1", PI, 0.0);
+	assert_circle("○0.5", PI / 2.0, 0.0);
+	assert_circle("○1J1", PI, PI);
+	test_eval_display("⍴○1 2 3", "3");
+	test_eval_fail("○'a'", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+}
+
+#[test]
+fn test_circle_real() {
+	let x: f64 = 0.5;
+	assert_circle("0○0.5", (1.0 - x * x).sqrt(), 0.0);
+	assert_circle("1○0.5", x.sin(), 0.0);
+	assert_circle("2○0.5", x.cos(), 0.0);
+	assert_circle("3○0.5", x.tan(), 0.0);
+	assert_circle("4○0.5", (1.0 + x * x).sqrt(), 0.0);
+	assert_circle("5○0.5", x.sinh(), 0.0);
+	assert_circle("6○0.5", x.cosh(), 0.0);
+	assert_circle("7○0.5", x.tanh(), 0.0);
+	assert_circle("8○0.5", 0.0, (1.0 + x * x).sqrt());
+	assert_circle("¯1○0.5", x.asin(), 0.0);
+	assert_circle("¯2○0.5", x.acos(), 0.0);
+	assert_circle("¯3○0.5", x.atan(), 0.0);
+	assert_circle("¯4○2", 3.0f64.sqrt(), 0.0);
+	assert_circle("¯4○¯2", -(3.0f64.sqrt()), 0.0);
+	assert_circle("¯5○0.5", x.asinh(), 0.0);
+	assert_circle("¯6○2", 2.0f64.acosh(), 0.0);
+	assert_circle("¯7○0.5", x.atanh(), 0.0);
+	assert_circle("¯8○0.5", 0.0, -(1.0 + x * x).sqrt());
+}
+
+#[test]
+fn test_circle_leaves_reals() {
+	//A real argument outside a function's domain gives a complex result
+	assert_circle("0○2", 0.0, 3.0f64.sqrt());
+	assert_circle("¯4○0.5", 0.0, 0.75f64.sqrt());
+	assert_circle("¯1○2", PI / 2.0, -(2.0 + 3.0f64.sqrt()).ln());
+	assert_circle("¯2○2", 0.0, (2.0 + 3.0f64.sqrt()).ln());
+	test_eval_display("8○0", "0J1");
+	test_eval_display("¯8○0", "0J-1");
+}
+
+#[test]
+fn test_circle_complex() {
+	assert_circle("0○3J4", 4.0803321728351385, -2.940937034462574);
+	assert_circle("1○3J4", 3.853738037919377, -27.016813258003932);
+	assert_circle("2○3J4", -27.034945603074224, -3.851153334811777);
+	assert_circle("3○3J4", -0.00018734620462947842, 0.9993559873814732);
+	assert_circle("4○3J4", 3.0609339876666697, 3.9203720329648544);
+	assert_circle("5○3J4", -6.5481200409110025, -7.61923172032141);
+	assert_circle("6○3J4", -6.580663040551157, -7.581552742746545);
+	assert_circle("7○3J4", 1.000709536067233, 0.00490825806749606);
+	assert_circle("8○3J4", 3.920372032964854, -3.0609339876666706);
+	assert_circle("¯1○3J4", 0.6339838656391701, 2.305509031243474);
+	assert_circle("¯2○3J4", 0.9368124611557199, -2.305509031243477);
+	assert_circle("¯3○3J4", 1.4483069952314644, 0.15899719167999926);
+	assert_circle("¯4○3J4", 2.940937034462574, 4.080332172835138);
+	assert_circle("¯5○3J4", 2.2999140408792695, 0.9176168533514787);
+	assert_circle("¯6○3J4", 2.305509031243477, 0.9368124611557199);
+	assert_circle("¯7○3J4", 0.11750090731143381, 1.4099210495965755);
+	assert_circle("¯8○3J4", -3.920372032964854, 3.0609339876666706);
+}
+
+#[test]
+fn test_circle_parts() {
+	test_eval_display("9○3J4", "3");
+	test_eval_display("10○3J4", "5");
+	test_eval_display("11○3J4", "4");
+	assert_circle("12○3J4", 4.0f64.atan2(3.0), 0.0);
+	test_eval_display("¯9○3J4", "3J4");
+	test_eval_display("¯10○3J4", "3J-4");
+	test_eval_display("¯11○3J4", "-4J3");
+	assert_circle("¯12○3J4", (-4.0f64).exp() * 3.0f64.cos(), (-4.0f64).exp() * 3.0f64.sin());
+	//Real arguments have no imaginary part
+	test_eval_display("9○0.5", "0.5");
+	test_eval_display("10○¯0.5", "0.5");
+	test_eval_display("11○0.5", "0");
+	test_eval_display("12○¯1", "3.141592653589793");
+}
+
+#[test]
+fn test_circle_errors() {
+	for input in ["13○1", "¯13○1", "1.5○1", "'a'○1"] {
+		test_eval_fail(input, |error| {
+			assert_eq!(error.to_string(), "DOMAIN ERROR: the left argument of ○ must be an integer from ¯12 to 12", "{}", input);
+		});
+	}
+	test_eval_fail("1○'a'", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	test_eval_fail("1 2○1 2 3", |error| {
+		assert!(matches!(error, AplError::Length(_, _)));
+	});
+	test_eval_display("1 2○0", "0 1");
+	test_eval_display("1○0 0", "0 0");
+}
@@ -0,0 +1,40 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_comparison_basic() {
+	test_eval_display("1 2 3=1 5 3", "1 0 1");
+	test_eval_display("1 2 3<2", "1 0 0");
+	test_eval_display("1 2 3≥2", "0 1 1");
+	test_eval_display("'abc'='abd'", "1 1 0");
+	test_eval_display("1='1'", "0");
+}
+
+#[test]
+fn test_comparison_tolerance() {
+	//Numbers within ⎕CT of each other, relative to the larger, are equal
+	test_eval_display("1=1.000000000000001", "1");
+	test_eval_display("1≠1.000000000000001", "0");
+	test_eval_display("1<1.000000000000001", "0");
+	test_eval_display("1≤1.000000000000001", "1");
+	test_eval_display("1=1.0000000000001", "0");
+	test_eval_display("3=3J0.0000000000000001", "1");
+	test_eval_display("1 2 3≠1.000000000000001 2 3.1", "0 0 1");
+}
+
+#[test]
+fn test_comparison_tolerance_setting() {
+	test_eval_display("⎕CT←0 ⋄ 1=1.000000000000001", "0");
+	test_eval_display("⎕CT←0.00001 ⋄ 1=1.000001", "1");
+	test_eval_display("⎕CT←0.00001 ⋄ 1>0.999999", "0");
+	//Integers are always compared exactly
+	test_eval_display("⎕CT←0.00001 ⋄ 1000000=1000001", "0");
+	test_eval_fail("⎕CT←¯1", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+}
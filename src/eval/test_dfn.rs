@@ -0,0 +1,79 @@
+use std::thread;
+use crate::{
+	error::AplError,
+	eval::{
+		environment::{
+			DEFAULT_STACK_SIZE,
+			EVALUATOR_STACK_SIZE,
+		},
+		eval::{
+			Evaluator,
+			Printable,
+		},
+		test_eval::test_eval_display,
+	},
+};
+
+#[test]
+fn test_dfn_basic() {
+	test_eval_display("{⍵+1} 2", "3");
+	test_eval_display("3 {⍺×⍵} 4", "12");
+	test_eval_display("{⍵=0:'zero' ⋄ 'other'} 0", "zero");
+}
+
+#[test]
+fn test_dfn_calls_later_function() {
+	//g is not a function yet when f is defined, so f's body first parses as the strand g ⍵
+	test_eval_display("f←{g ⍵} ⋄ g←{⍵+1} ⋄ f 1", "2");
+	test_eval_display("f←{⍺ g ⍵} ⋄ g←{⍺×⍵} ⋄ 3 f 4", "12");
+	//And back again when g stops being a function
+	test_eval_display("f←{g ⍵} ⋄ g←{⍵+1} ⋄ f 1 ⋄ g←5 ⋄ f 1", "5 1");
+}
+
+#[test]
+fn test_dfn_mutual_recursion() {
+	let program = "even←{⍵=0:1 ⋄ odd ⍵-1} ⋄ odd←{⍵=0:0 ⋄ even ⍵-1}";
+	test_eval_display(&format!("{} ⋄ even 10", program), "1");
+	test_eval_display(&format!("{} ⋄ odd 7", program), "1");
+	test_eval_display(&format!("{} ⋄ even 7", program), "0");
+}
+
+//Evaluates on a thread of the given stack size, with an evaluator told its size
+fn eval_on_stack(size: usize, input: &'static str) -> Result<String, AplError> {
+	thread::Builder::new()
+		.stack_size(size)
+		.spawn(move || {
+			let mut evaluator = Evaluator::with_stack_size(size);
+			evaluator.eval(input.to_string()).map(|result| result.map(|value| value.to_string()).unwrap_or_default())
+		})
+		.unwrap()
+		.join()
+		.unwrap()
+}
+
+#[test]
+fn test_dfn_deep_recursion() {
+	let deep = |input| eval_on_stack(EVALUATOR_STACK_SIZE, input);
+	assert_eq!(deep("count←{⍵=0:0 ⋄ 1+∇ ⍵-1} ⋄ count 1000"), Ok("1000".to_string()));
+	assert_eq!(deep("fact←{⍵=0:1 ⋄ ⍵×∇ ⍵-1} ⋄ (fact 20)=!20"), Ok("1".to_string()));
+	//Its result overflows to a float, but 300 calls deep is no trouble
+	assert_eq!(deep("fact←{⍵=0:1 ⋄ ⍵×∇ ⍵-1} ⋄ fact 300"), Ok("inf".to_string()));
+	assert_eq!(deep("sum←{⍵=0:0 ⋄ ⍵+∇ ⍵-1} ⋄ sum 300"), Ok("45150".to_string()));
+	assert!(matches!(deep("loop←{∇ ⍵} ⋄ loop 1"), Err(AplError::Limit(_, _))));
+}
+
+#[test]
+fn test_dfn_recursion_limit() {
+	//However small the stack, endless recursion is a limit error and never overflows it
+	for size in [DEFAULT_STACK_SIZE, 8 * 1024 * 1024] {
+		assert!(matches!(eval_on_stack(size, "loop←{∇ ⍵} ⋄ loop 1"), Err(AplError::Limit(_, _))));
+		assert!(matches!(eval_on_stack(size, "loop←{1+∇ ⍵} ⋄ loop 1"), Err(AplError::Limit(_, _))));
+		match eval_on_stack(size, "count←{⍵=0:0 ⋄ 1+∇ ⍵-1} ⋄ count 1000") {
+			Ok(result) => assert_eq!(result, "1000"),
+			Err(error) => assert!(matches!(error, AplError::Limit(_, _)), "{}", error)
+		}
+	}
+	//An evaluator that isn't told otherwise assumes a new thread's stack
+	let result = thread::spawn(|| Evaluator::new().eval("loop←{∇ ⍵} ⋄ loop 1".to_string()).map(|_| ())).join().unwrap();
+	assert!(matches!(result, Err(AplError::Limit(_, _))));
+}
@@ -0,0 +1,40 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_disclose_basic() {
+	test_eval_display("⊃(1 2)(3 4)", "1 2\n3 4");
+	test_eval_display("⊃(1 2 3)(4)", "1 2 3\n4 0 0");
+}
+
+#[test]
+fn test_disclose_empty_fill() {
+	//The empty item is padded with spaces, like the others
+	test_eval_display("⍴⊃'ab' ''", "2 2");
+	test_eval_display("(⊃'ab' '')=2 2⍴'ab  '", "1 1\n1 1");
+	test_eval_display("(⊃'abc' 'd')=2 3⍴'abcd  '", "1 1 1\n1 1 1");
+}
+
+#[test]
+fn test_disclose_depth() {
+	//Disclose undoes one enclose at a time
+	test_eval_display("⊃⊂1 2", "1 2");
+	test_eval_display("⊃⊂⊂1 2", " 1 2");
+	test_eval_display("⊃⊃⊂⊂1 2", "1 2");
+	test_eval_display("⊃5", "5");
+	test_eval_display("⊃1 2 3", "1 2 3");
+	test_eval_display("⍴⊃(1 2)(3 4 5)", "2 3");
+	test_eval_display("⍴⊃2 2⍴⊂1 2 3", "2 2 3");
+}
+
+#[test]
+fn test_disclose_rank() {
+	test_eval_fail("⊃(2 2⍴1)(1 2)", |error| {
+		assert!(matches!(error, AplError::Rank(_, _)));
+	});
+}
@@ -0,0 +1,49 @@
+use crate::eval::{
+	eval::Printable,
+	test_eval::{
+		test_eval,
+		test_eval_display,
+	},
+};
+
+#[test]
+fn test_display_matrix() {
+	test_eval_display("2 3⍴⍳6", "1 2 3\n4 5 6");
+	//Columns are right aligned to their widest item
+	test_eval_display("2 2⍴1 10 100 1000", "  1   10\n100 1000");
+	test_eval_display("2 2⍴1.5 2 ¯3 4", "1.5 2\n -3 4");
+	test_eval_display("2 2⍴1J2 3 4 5", "1J2 3\n  4 5");
+	test_eval_display("2 2⍴'abcd'", "ab\ncd");
+	test_eval_display("3 1⍴1 2 3", "1\n2\n3");
+}
+
+#[test]
+fn test_display_higher_rank() {
+	//Planes are separated by a blank line, and a blank line more for each rank above
+	test_eval_display("2 2 2⍴⍳8", "1 2\n3 4\n\n5 6\n7 8");
+	test_eval_display("2 1 2 1⍴⍳4", "1\n2\n\n\n3\n4");
+	test_eval_display("1 1 1⍴5", "5");
+}
+
+#[test]
+fn test_display_nested() {
+	test_eval_display("2 2⍴(1 2)(3 4)5 6", " 1 2  3 4\n   5    6");
+	test_eval_display("3⍴⊂1 2", " 1 2  1 2  1 2");
+}
+
+#[test]
+fn test_display_empty() {
+	test_eval_display("0 3⍴0", "");
+	test_eval_display("1 0⍴0", "");
+	test_eval_display("⍴0 3⍴0", "0 3");
+}
+
+#[test]
+fn test_display_typed() {
+	test_eval("2 2⍴⍳4", |result| {
+		assert_eq!(result.to_typed_string(), "ARRAY[2 2](1 2\n3 4)");
+	});
+	test_eval("2 1 2⍴'abcd'", |result| {
+		assert_eq!(result.to_typed_string(), "ARRAY[2 1 2](ab\n\ncd)");
+	});
+}
@@ -0,0 +1,29 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_each_monadic() {
+	test_eval_display("-¨1 2 3", "-1 -2 -3");
+	test_eval_display("⍴¨(1 2)(3 4 5)", " 2  3");
+	test_eval_display("⍳¨2 3", " 1 2  1 2 3");
+	test_eval_display("⍴⍳¨2 3", "2");
+	test_eval_display("⍴⍴¨⍬", "0");
+	test_eval_display("{⍵×2}¨(1 2)3", " 2 4  6");
+}
+
+#[test]
+fn test_each_dyadic() {
+	test_eval_display("1 2+¨3 4", "4 6");
+	//A scalar goes with every item of the other side
+	test_eval_display("1+¨(1 2)(3 4)", " 2 3  4 5");
+	test_eval_display("(1 2)(3 4),¨5", " 1 2 5  3 4 5");
+	test_eval_display("2 3⍴¨1 2", " 1 1  2 2 2");
+	test_eval_fail("1 2+¨1 2 3", |error| {
+		assert!(matches!(error, AplError::Length(_, _)));
+	});
+}
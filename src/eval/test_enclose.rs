@@ -0,0 +1,18 @@
+use crate::eval::test_eval::test_eval_display;
+
+#[test]
+fn test_enclose_basic() {
+	test_eval_display("⊂1 2", " 1 2");
+	test_eval_display("⍴⍴⊂1 2", "0");
+	test_eval_display("⊂⊂1 2", "  1 2");
+	test_eval_display("(⊂1 2),⊂3", " 1 2  3");
+	test_eval_display("⊂¨1 2", "1 2");
+}
+
+#[test]
+fn test_enclose_simple_scalar() {
+	//A simple scalar is its own enclosure
+	test_eval_display("⊂5", "5");
+	test_eval_display("⍴⍴⊂5", "0");
+	test_eval_display("(⊂5)=5", "1");
+}
@@ -0,0 +1,58 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::test_eval_fail,
+};
+
+//Checks the class, message and position an input fails with
+fn assert_error(input: &str, class: &str, message: &str, row: usize, col: usize) {
+	test_eval_fail(input, |error| {
+		assert_eq!((error.class(), error.message()), (class, message), "{}", input);
+		let span = error.span().unwrap_or_else(|| panic!("{} - {} has no position", input, error));
+		assert_eq!((span.row, span.col), (row, col), "{}", input);
+	});
+}
+
+#[test]
+fn test_error_display() {
+	assert_eq!(AplError::length("").to_string(), "LENGTH ERROR");
+	assert_eq!(AplError::domain("division by zero").to_string(), "DOMAIN ERROR: division by zero");
+	assert_eq!(AplError::value("x is not defined").at_position(2, 3).to_string(), "VALUE ERROR: x is not defined");
+	assert_eq!(AplError::nonce("").class(), "NONCE ERROR");
+	assert_eq!(AplError::index("").span(), None);
+}
+
+#[test]
+fn test_error_position() {
+	let error = AplError::rank("").at_position(1, 5);
+	assert_eq!(error.span().map(|span| (span.row, span.col)), Some((1, 5)));
+	assert_eq!(error.clone().at_position(3, 2).span().map(|span| (span.row, span.col)), Some((3, 2)));
+}
+
+#[test]
+fn test_error_eval_classes() {
+	assert_error("1 2+1 2 3", "LENGTH ERROR", "", 1, 4);
+	assert_error("1÷0", "DOMAIN ERROR", "division by zero", 1, 2);
+	assert_error("(2 2⍴1)+1 2", "RANK ERROR", "", 1, 8);
+	assert_error("1 2↑[3]1 2", "AXIS ERROR", "this function takes no axis", 1, 4);
+	assert_error("{⍺+⍵} 1", "VALUE ERROR", "⍺ is not defined", 1, 2);
+	assert_error("x", "VALUE ERROR", "x is not defined", 1, 1);
+}
+
+#[test]
+fn test_error_eval_spans() {
+	//The error points at the primitive that failed, not the first in the line
+	assert_error("1+2+3×4÷0", "DOMAIN ERROR", "division by zero", 1, 8);
+	assert_error("1+{⍵÷0} 2", "DOMAIN ERROR", "division by zero", 1, 5);
+	assert_error("1 2 3+⍳2", "LENGTH ERROR", "", 1, 6);
+	assert_error("1\n2\n3÷0", "DOMAIN ERROR", "division by zero", 3, 2);
+	assert_error("1 ⋄ 1÷0", "DOMAIN ERROR", "division by zero", 1, 6);
+}
+
+#[test]
+fn test_error_syntax_spans() {
+	assert_error("1 $ 2", "SYNTAX ERROR", "no valid token starts with $", 1, 3);
+	assert_error("1 2.3.4", "SYNTAX ERROR", "invalid number", 1, 3);
+	assert_error("'abc", "SYNTAX ERROR", "unterminated string", 1, 1);
+	assert_error("2 3⍴)", "SYNTAX ERROR", "unmatched )", 1, 5);
+	assert_error("2 3⍴\n1", "SYNTAX ERROR", "unexpected token", 1, 5);
+}
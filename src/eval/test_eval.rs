@@ -1,53 +1,71 @@
-use std::result;
-use eval::eval;
-use eval::eval::Evaluator;
+use crate::{
+	error::AplError,
+	eval::eval::{
+		Evaluator,
+		Printable,
+		Value,
+	},
+};
 
-pub fn test_eval(input: ~str, f: |result: ~eval::Value|) {
-
-	let mut eval = Evaluator::new(input);
-	match eval.eval() {
-		result::Ok(result) => {
+pub fn test_eval<F>(input: &str, f: F) where F: FnOnce(Box<Value>) {
+	let mut eval = Evaluator::new();
+	match eval.eval(input.to_string()) {
+		Ok(Some(result)) => {
 			f(result)
 		},
-		result::Err(msg) => {
-			fail!(format!("{} - {}", eval.parser.tokenizer.char_reader.source, msg))
+		Ok(None) => {
+			panic!("{} - gave no value", input)
+		},
+		Err(error) => {
+			panic!("{} - {}", input, error)
 		}
 	}
 }
 
-pub fn test_eval_fail(input: ~str, f: |result: ~str|) {
-
-	let mut eval = Evaluator::new(input);
-	match eval.eval() {
-		result::Ok(_) => {
-			fail!(format!("{} - incorrectly gave a success", eval.parser.tokenizer.char_reader.source))
+pub fn test_eval_fail<F>(input: &str, f: F) where F: FnOnce(AplError) {
+	let mut eval = Evaluator::new();
+	match eval.eval(input.to_string()) {
+		Ok(_) => {
+			panic!("{} - incorrectly gave a success", input)
 		},
-		result::Err(msg) => {
-			f(msg)
+		Err(error) => {
+			f(error)
 		}
 	}
 }
 
+//Checks a result as the session would display it, which is the easiest way to compare arrays
+pub fn test_eval_display(input: &str, expected: &str) {
+	test_eval(input, |result| {
+		assert_eq!(result.to_string(), expected, "{}", input);
+	});
+}
+
+//Results given to a handful of places
+pub fn assert_approx_eq(actual: f64, expected: f64) {
+	assert!((actual - expected).abs() <= 1e-4 * expected.abs().max(1.0), "{} is not about {}", actual, expected);
+}
+
 #[test]
 fn test_eval_int() {
-	test_eval(~"3", |result| {
-		match result {
-			~eval::AplInteger(x) => {
+	test_eval("3", |result| {
+		match *result {
+			Value::AplInteger(x) => {
 				assert_eq!(x, 3);
 			},
 			_ => {
-				fail!(~"Didn't find a number");
+				panic!("Didn't find a number");
 			}
 		}
 	});
 
-	test_eval(~"¯3", |result| {
-		match result {
-			~eval::AplInteger(x) => {
+	test_eval("¯3", |result| {
+		match *result {
+			Value::AplInteger(x) => {
 				assert_eq!(x, -3);
 			},
 			_ => {
-				fail!(~"Didn't find a number");
+				panic!("Didn't find a number");
 			}
 		}
 	})
@@ -55,23 +73,23 @@ fn test_eval_int() {
 
 #[test]
 fn test_eval_float() {
-	test_eval(~".2", |result| {
-		match result {
-			~eval::AplFloat(x) => {
+	test_eval(".2", |result| {
+		match *result {
+			Value::AplFloat(x) => {
 				assert_eq!(x, 0.2f64);
 			},
 			_ => {
-				fail!(~"Didn't find a number");
+				panic!("Didn't find a number");
 			}
 		}
 	});
-	test_eval(~"¯.2", |result| {
-		match result {
-			~eval::AplFloat(x) => {
+	test_eval("¯.2", |result| {
+		match *result {
+			Value::AplFloat(x) => {
 				assert_eq!(x, -0.2f64);
 			},
 			_ => {
-				fail!(~"Didn't find a number");
+				panic!("Didn't find a number");
 			}
 		}
 	})
@@ -79,27 +97,99 @@ fn test_eval_float() {
 
 #[test]
 fn test_eval_complex() {
-	test_eval(~"1J3", |result| {
-		match result {
-			~eval::AplComplex(c) => {
+	test_eval("1J3", |result| {
+		match *result {
+			Value::AplComplex(c) => {
 				assert_eq!(c.re, 1.0f64);
 				assert_eq!(c.im, 3.0f64);
 			},
 			_ => {
-				fail!(~"Didn't find a number");
+				panic!("Didn't find a number");
 			}
 		}
 	});
 
-	test_eval(~"¯1J.2", |result| {
-		match result {
-			~eval::AplComplex(c) => {
+	test_eval("¯1J.2", |result| {
+		match *result {
+			Value::AplComplex(c) => {
 				assert_eq!(c.re, -1.0f64);
 				assert!(c.im == 0.2f64);
 			},
 			_ => {
-				fail!(~"Didn't find a number");
+				panic!("Didn't find a number");
 			}
 		}
 	});
 }
+
+#[test]
+fn test_eval_char() {
+	test_eval("'a'", |result| {
+		assert!(*result == Value::AplChar('a'));
+	});
+	test_eval("''''", |result| {
+		assert!(*result == Value::AplChar('\''));
+	});
+	test_eval_display("⍴⍴'a'", "0");
+}
+
+#[test]
+fn test_eval_string() {
+	test_eval_display("'hello'", "hello");
+	test_eval_display("⍴'hello'", "5");
+	//A doubled quote stands for one quote
+	test_eval_display("'it''s'", "it's");
+	test_eval_display("⍴'it''s'", "4");
+	test_eval_display("⍴''''''", "2");
+	test_eval_display("''", "");
+	test_eval_display("⍴''", "0");
+	test_eval_display("⍴'' ''", "2");
+	test_eval_display("'ab' 'cd'", " ab  cd");
+	test_eval_display("'ab','cd'", "abcd");
+	test_eval_display("2 2⍴'abcd'", "ab\ncd");
+}
+
+#[test]
+fn test_eval_mixed() {
+	test_eval_display("1 'a' 2", "1 a 2");
+	test_eval_display("'a' 1.5", "a 1.5");
+	test_eval_display("'abc'='abd'", "1 1 0");
+	//Characters are never equal to numbers
+	test_eval_display("'a'=97", "0");
+	test_eval_display("'1'=1", "0");
+	test_eval_fail("'a'<'b'", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+}
+
+#[test]
+fn test_eval_failure() {
+	test_eval_fail("1+'a'", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+}
+
+#[test]
+fn test_eval_unmatched_parenthesis() {
+	for input in ["1+(\n2", "1+(⋄2", "1+("] {
+		test_eval_fail(input, |error| {
+			assert_eq!(error, AplError::syntax("unmatched (").at_position(1, 3), "{}", input);
+		});
+	}
+}
+
+#[test]
+fn test_eval_nesting_limit() {
+	let nested = |depth: usize| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+	test_eval_display(&nested(100), "1");
+	test_eval_display(&format!("{}1", "-".repeat(100)), "1");
+	//Far too deep to recurse through, so it is refused instead of overflowing the stack
+	for input in [nested(100000), format!("{}⍵{}", "{".repeat(100000), "}".repeat(100000)), format!("{}1", "-".repeat(100000))] {
+		test_eval_fail(&input, |error| {
+			assert!(matches!(error, AplError::Limit(_, _)), "{}", error);
+		});
+	}
+	test_eval_fail(&nested(200), |error| {
+		assert_eq!(error, AplError::limit("the expression is nested too deeply").at_position(1, 129));
+	});
+}
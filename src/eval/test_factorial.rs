@@ -0,0 +1,52 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		test_eval::{
+			test_eval,
+			test_eval_display,
+			test_eval_fail,
+		},
+	},
+};
+
+#[test]
+fn test_factorial_integer() {
+	test_eval_display("!0 1 5", "1 1 120");
+	test_eval_display("!20", "2432902008176640000");
+	test_eval("!170", |result| {
+		match *result {
+			Value::AplFloat(x) => {
+				assert!(x.is_finite());
+			},
+			_ => {
+				panic!("Didn't find a float");
+			}
+		}
+	});
+}
+
+#[test]
+fn test_factorial_too_large() {
+	for input in ["!171", "!200", "!1000", "!171.5", "!200J1"] {
+		test_eval_fail(input, |error| {
+			assert!(matches!(error, AplError::Domain(_, _)), "{}", input);
+		});
+	}
+}
+
+#[test]
+fn test_binomial() {
+	test_eval_display("2!4", "6");
+	test_eval_display("10!20", "184756");
+	test_eval_display("3!¯2", "-4");
+}
+
+#[test]
+fn test_binomial_too_large() {
+	for input in ["600!1200", "1J1!200", "0.5!1000.5"] {
+		test_eval_fail(input, |error| {
+			assert!(matches!(error, AplError::Domain(_, _)), "{}", input);
+		});
+	}
+}
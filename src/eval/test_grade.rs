@@ -0,0 +1,88 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_grade_vectors() {
+	test_eval_display("⍋3 1 2", "2 3 1");
+	test_eval_display("⍒3 1 2", "1 3 2");
+	test_eval_display("⍋1.5 ¯2 1", "2 3 1");
+	test_eval_display("⍋'banana'", "2 4 6 1 3 5");
+	test_eval_display("⎕IO←0 ⋄ ⍋3 1 2", "1 2 0");
+}
+
+#[test]
+fn test_grade_stability() {
+	//Equal items keep their original order, whichever way the grade goes
+	test_eval_display("⍋3 1 2 1 3", "2 4 3 1 5");
+	test_eval_display("⍒3 1 2 1 3", "1 5 3 2 4");
+	test_eval_display("⍒'banana'", "3 5 1 2 4 6");
+}
+
+#[test]
+fn test_grade_matrix_rows() {
+	//Rows are ordered lexicographically, and equal rows stay in order
+	test_eval_display("⍋3 2⍴1 2 1 1 0 5", "3 2 1");
+	test_eval_display("⍒3 2⍴1 2 1 1 0 5", "1 2 3");
+	test_eval_display("⍋2 2⍴1 2 1 2", "1 2");
+	test_eval_display("⍋3 3⍴'catcabcar'", "2 3 1");
+	test_eval_display("⍋2 2 2⍴8 7 6 5 4 3 2 1", "2 1");
+}
+
+#[test]
+fn test_grade_empty() {
+	test_eval_display("⍴⍋⍬", "0");
+	test_eval_display("⍴⍋0 3⍴0", "0");
+}
+
+#[test]
+fn test_collated_grade() {
+	test_eval_display("'ab'⍋'baab'", "2 3 1 4");
+	test_eval_display("'ab'⍒'baab'", "1 4 2 3");
+	//Characters missing from the sequence come after every one in it
+	test_eval_display("'ab'⍋'zab'", "2 3 1");
+}
+
+#[test]
+fn test_collated_grade_matrix_sequence() {
+	//Letters are ordered alphabetically first, and only then by case
+	test_eval_display("(2 3⍴'abcABC')⍋3 2⍴'BaabAb'", "2 3 1");
+	test_eval_display("(2 3⍴'abcABC')⍋4 1⍴'bBaA'", "3 4 1 2");
+	test_eval_display("(2 3⍴'abcABC')⍒4 1⍴'bBaA'", "2 1 4 3");
+}
+
+#[test]
+fn test_grade_errors() {
+	test_eval_fail("⍋1J1 2", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	test_eval_fail("⍒2 1J1", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	test_eval_fail("⍋,1J1", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	test_eval_fail("⍋1 'a'", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	//Long enough that the sort would be handed an inconsistent order before the check
+	test_eval_fail("⍋(⍳20),'ab',(⌽⍳20),'cd',(⍳20)", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	test_eval_fail("⍒(⍳20),'ab',(⌽⍳20),'cd',(⍳20)", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	test_eval_fail("⍋(1 2)(3 4)", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	test_eval_fail("'ab'⍋1 2", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	test_eval_fail("⍋5", |error| {
+		assert!(matches!(error, AplError::Rank(_, _)));
+	});
+}
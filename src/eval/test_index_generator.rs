@@ -0,0 +1,49 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_index_generator_basic() {
+	test_eval_display("⍳5", "1 2 3 4 5");
+	test_eval_display("⍳1", "1");
+	test_eval_display("⍴⍳1", "1");
+	test_eval_display("⍴⍳0", "0");
+}
+
+#[test]
+fn test_index_generator_origin() {
+	test_eval_display("⎕IO←0 ⋄ ⍳5", "0 1 2 3 4");
+	test_eval_display("⎕IO←0 ⋄ ⍳2 2", " 0 0  0 1\n 1 0  1 1");
+	test_eval_display("⎕IO←0 ⋄ ⎕IO←1 ⋄ ⍳3", "1 2 3");
+	test_eval_fail("⎕IO←2", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+}
+
+#[test]
+fn test_index_generator_shape() {
+	//A vector gives an array of index pairs, one for each position
+	test_eval_display("⍳2 3", " 1 1  1 2  1 3\n 2 1  2 2  2 3");
+	test_eval_display("⍴⍳2 3", "2 3");
+	test_eval_display("⍴⍴⍳⍬", "0");
+}
+
+#[test]
+fn test_index_generator_errors() {
+	test_eval_fail("⍳¯1", |error| {
+		assert_eq!(error, AplError::domain("negative index count").at_position(1, 1));
+	});
+	test_eval_fail("⍳2.5", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	test_eval_fail("⍳'a'", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	test_eval_fail("⍳2 2⍴1", |error| {
+		assert!(matches!(error, AplError::Rank(_, _)));
+	});
+}
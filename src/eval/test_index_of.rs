@@ -0,0 +1,46 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_index_of_basic() {
+	test_eval_display("1 2 3⍳2", "2");
+	//Items that aren't found give one past the end
+	test_eval_display("1 2 3⍳4", "4");
+	test_eval_display("1 2 2⍳2 4", "2 4");
+	test_eval_display("'abc'⍳'cax'", "3 1 4");
+	test_eval_display("⍬⍳1 2", "1 1");
+	test_eval_display("(1 2)(3 4)⍳⊂3 4", "2");
+	test_eval_display("'ab' 1⍳1 'b'", "2 3");
+	//The result has the shape of the right argument
+	test_eval_display("1 2 3⍳2 2⍴1 2 3 4", "1 2\n3 4");
+}
+
+#[test]
+fn test_index_of_origin() {
+	test_eval_display("⎕IO←0 ⋄ 1 2 3⍳3 4", "2 3");
+	test_eval_display("⎕IO←0 ⋄ 'abc'⍳'c'", "2");
+}
+
+#[test]
+fn test_index_of_rank() {
+	test_eval_fail("(2 2⍴1)⍳1", |error| {
+		assert!(matches!(error, AplError::Rank(_, _)));
+	});
+}
+
+#[test]
+fn test_index_of_tolerance() {
+	//Items are found as = would find them, within ⎕CT
+	test_eval_display("0.1 0.2⍳0.1+0.1", "2");
+	test_eval_display("0.3 1⍳0.1+0.2", "1");
+	test_eval_display("⎕CT←0 ⋄ 0.3 1⍳0.1+0.2", "3");
+	test_eval_display("1 2 3⍳3.0000000000000004", "3");
+	test_eval_display("1J1 2⍳1J1.0000000000000002", "1");
+	//Characters never match numbers
+	test_eval_display("'1' 1⍳1", "2");
+}
@@ -0,0 +1,40 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_inner_product_basic() {
+	test_eval_display("1 2 3+.×4 5 6", "32");
+	test_eval_display("(2 2⍴1 2 3 4)+.×2 2⍴5 6 7 8", "19 22\n43 50");
+	test_eval_display("1 2 3∧.=1 2 3", "1");
+	test_eval_display("1 2 3∧.=1 5 3", "0");
+	test_eval_display("1 2 3⌈.+3 2 1", "4");
+	//A scalar is extended along the shared axis
+	test_eval_display("2+.×1 2 3", "12");
+}
+
+#[test]
+fn test_inner_product_shape() {
+	//The last axis of the left meets the first axis of the right
+	test_eval_display("⍴(2 3⍴1)+.×3 4⍴1", "2 4");
+	test_eval_display("⍴(2 3⍴1)+.×3", "2");
+	test_eval_display("⍴(0 3⍴0)+.×3 2⍴1", "0 2");
+	//An empty shared axis gives the identity of the reduction
+	test_eval_display("(2 0⍴0)+.×0 3⍴0", "0 0 0\n0 0 0");
+	test_eval_display("(2 0⍴0)×.+0 3⍴0", "1 1 1\n1 1 1");
+	test_eval_display("⍬+.×⍬", "0");
+}
+
+#[test]
+fn test_inner_product_errors() {
+	test_eval_fail("(2 3⍴1)+.×2 3⍴1", |error| {
+		assert!(matches!(error, AplError::Length(_, _)));
+	});
+	test_eval_fail("1 2+.×1 2 3", |error| {
+		assert!(matches!(error, AplError::Length(_, _)));
+	});
+}
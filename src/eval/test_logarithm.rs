@@ -0,0 +1,40 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		test_eval::{
+			test_eval,
+			test_eval_display,
+			test_eval_fail,
+		},
+	},
+};
+
+#[test]
+fn test_logarithm_base() {
+	test_eval_display("2⍟8", "3");
+	test_eval_display("10⍟100", "2");
+	test_eval_display("3⍟1", "0");
+}
+
+#[test]
+fn test_logarithm_base_one() {
+	test_eval("1⍟1", |result| {
+		match *result {
+			Value::AplFloat(x) => {
+				assert_eq!(x, 1.0);
+			},
+			_ => {
+				panic!("Didn't find a float");
+			}
+		}
+	});
+	test_eval_display("1⍟1J0", "1");
+	test_eval_display("(1J0)⍟1", "1");
+	test_eval_fail("1⍟2", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	test_eval_fail("1⍟1 2", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+}
@@ -0,0 +1,75 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_lcm_zero() {
+	//Zero with a negative number is 0, never -0
+	test_eval_display("0∧¯9223372036854775807-1", "0");
+	test_eval_display("(¯9223372036854775807-1)∧0", "0");
+	test_eval_display("0∧¯9223372036854775808", "0");
+	test_eval_display("0∧¯5.5", "0");
+	test_eval_display("¯2.5∧0", "0");
+	test_eval_display("0∧0", "0");
+}
+
+#[test]
+fn test_lcm_integer_extremes() {
+	test_eval_display("(¯9223372036854775807-1)∧2", "-9223372036854775808");
+	test_eval_display("9223372036854775807∧1", "9223372036854775807");
+	test_eval_display("¯9223372036854775807∧¯1", "9223372036854775807");
+	//A multiple too large for an integer carries on as a float
+	test_eval_display("9223372036854775807∧2", "18446744073709552000");
+	//As does a divisor of 2⋆63
+	test_eval_display("(¯9223372036854775807-1)∨0", "9223372036854776000");
+}
+
+#[test]
+fn test_logical_booleans() {
+	test_eval_display("0 0 1 1∧0 1 0 1", "0 0 0 1");
+	test_eval_display("0 0 1 1∨0 1 0 1", "0 1 1 1");
+	test_eval_display("0 0 1 1⍲0 1 0 1", "1 1 1 0");
+	test_eval_display("0 0 1 1⍱0 1 0 1", "1 0 0 0");
+	test_eval_display("~0 1", "1 0");
+	test_eval_display("~1.0", "0");
+}
+
+#[test]
+fn test_logical_domain() {
+	//Not, nand and nor only take booleans
+	for input in ["~2", "~0.5", "~'a'", "1⍲2", "2⍱0", "0.5⍱1", "1J1⍲1"] {
+		test_eval_fail(input, |error| {
+			assert_eq!(error.to_string(), "DOMAIN ERROR: expected a boolean", "{}", input);
+		});
+	}
+	for input in ["'a'∧1", "1∨'a'", "0∧'a'"] {
+		test_eval_fail(input, |error| {
+			assert!(matches!(error, AplError::Domain(_, _)), "{}", input);
+		});
+	}
+	test_eval_fail("1 2 3∨1 2", |error| {
+		assert!(matches!(error, AplError::Length(_, _)));
+	});
+}
+
+#[test]
+fn test_gcd() {
+	test_eval_display("12∨18", "6");
+	test_eval_display("¯12∨18", "6");
+	test_eval_display("0∨5", "5");
+	test_eval_display("0∨0", "0");
+	test_eval_display("1.5∨2.5", "0.5");
+	test_eval_display("3J4∨6J8", "3J4");
+}
+
+#[test]
+fn test_lcm() {
+	test_eval_display("12∧18", "36");
+	test_eval_display("¯12∧18", "-36");
+	test_eval_display("1.5∧2.5", "7.5");
+	test_eval_display("2J2∧1J1", "2J2");
+}
@@ -0,0 +1,34 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_outer_product_basic() {
+	test_eval_display("1 2 3∘.×1 2", "1 2\n2 4\n3 6");
+	test_eval_display("2∘.+1 2", "3 4");
+	test_eval_display("1 2∘.,3 4", " 1 3  1 4\n 2 3  2 4");
+	test_eval_display("1 2∘.=1 2", "1 0\n0 1");
+}
+
+#[test]
+fn test_outer_product_shape() {
+	//The shape is the left shape followed by the right shape
+	test_eval_display("⍴(2 2⍴1)∘.+1 2 3", "2 2 3");
+	test_eval_display("⍴1 2∘.+2 3⍴1", "2 2 3");
+	test_eval_display("⍴⍴2∘.+3", "0");
+	test_eval_display("⍴⍬∘.+1 2", "0 2");
+}
+
+#[test]
+fn test_outer_product_errors() {
+	test_eval_fail("1 2∘.÷0", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	test_eval_fail("1 2∘.+'a'", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+}
@@ -0,0 +1,59 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::{
+			Evaluator,
+			Printable,
+		},
+		test_eval::test_eval_display,
+	},
+};
+
+//Runs a program, giving each value it shows along with how it ended
+fn run(source: &str) -> (Vec<String>, Result<(), AplError>) {
+	let mut eval = Evaluator::new();
+	let mut shown = vec![];
+	let result = eval.run_program(source.to_string(), |value| shown.push(value.to_string()));
+	(shown, result)
+}
+
+#[test]
+fn test_program_statements() {
+	assert_eq!(run("1\n2 3\n'a'"), (vec!["1".to_string(), "2 3".to_string(), "a".to_string()], Ok(())));
+	assert_eq!(run("1 ⋄ 2 ⋄ 3"), (vec!["1".to_string(), "2".to_string(), "3".to_string()], Ok(())));
+	assert_eq!(run("1 ⋄ 2\n3"), (vec!["1".to_string(), "2".to_string(), "3".to_string()], Ok(())));
+	//Empty statements are skipped
+	assert_eq!(run("\n\n1\n\n⋄⋄2\n"), (vec!["1".to_string(), "2".to_string()], Ok(())));
+	assert_eq!(run(""), (vec![], Ok(())));
+}
+
+#[test]
+fn test_program_state() {
+	//Assignments aren't shown, and later statements see them
+	assert_eq!(run("x←1\ny←x+1 ⋄ y\nx"), (vec!["2".to_string(), "1".to_string()], Ok(())));
+	assert_eq!(run("f←{⍵×2}\nf 3"), (vec!["6".to_string()], Ok(())));
+	assert_eq!(run("⎕IO←0\n⍳3"), (vec!["0 1 2".to_string()], Ok(())));
+}
+
+#[test]
+fn test_program_stops_at_error() {
+	let (shown, result) = run("1\n2÷0\n3");
+	assert_eq!(shown, vec!["1".to_string()]);
+	assert_eq!(result, Err(AplError::domain("division by zero").at_position(2, 2)));
+	//Each statement is parsed as it is reached, so those before a syntax error still run
+	let (shown, result) = run("1 ⋄ 2 ⋄ (");
+	assert_eq!(shown, vec!["1".to_string(), "2".to_string()]);
+	assert!(matches!(result, Err(AplError::Syntax(_, _))));
+}
+
+#[test]
+fn test_program_dfn_lines() {
+	assert_eq!(run("f←{\n  x←⍵+1\n  x×2\n}\nf 1"), (vec!["4".to_string()], Ok(())));
+	assert_eq!(run("{x←⍵ ⋄ x+1} 1"), (vec!["2".to_string()], Ok(())));
+}
+
+#[test]
+fn test_eval_gives_last_value() {
+	test_eval_display("1 ⋄ 2", "2");
+	test_eval_display("x←3 ⋄ x+1", "4");
+}
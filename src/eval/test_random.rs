@@ -0,0 +1,57 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval,
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_random_link_sequence() {
+	//The first step of the minimal standard generator from its usual seed
+	test_eval_display("?0 ⋄ ⎕RL", "282475249");
+	//A given ⎕RL always gives the same numbers
+	test_eval_display("⎕RL←7 ⋄ 5?10", "9 5 6 7 2");
+	test_eval_display("⎕RL←7 ⋄ ?6 6 6", "1 1 6");
+	test_eval_display("⎕RL←7 ⋄ ?6 6 6 ⋄ ⎕RL", "621132276");
+	test_eval_display("⎕RL←42 ⋄ a←5?100 ⋄ ⎕RL←42 ⋄ ∧/a=5?100", "1");
+}
+
+#[test]
+fn test_roll() {
+	test_eval("⍴?2 3⍴6", |result| {
+		assert_eq!(result.shape(), vec![2]);
+	});
+	test_eval_display("?1 1 1", "1 1 1");
+	test_eval_display("⎕IO←0 ⋄ ?1 1 1", "0 0 0");
+	test_eval_fail("?¯1", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	test_eval_fail("?1.5", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+}
+
+#[test]
+fn test_deal() {
+	//Dealing every number gives each of them once
+	test_eval_display("+/(⍳10)∘.=10?10", "1 1 1 1 1 1 1 1 1 1");
+	test_eval_display("⍴0?5", "0");
+	test_eval_fail("4?3", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	test_eval_fail("1 2?3", |error| {
+		assert!(matches!(error, AplError::Length(_, _)));
+	});
+}
+
+#[test]
+fn test_random_link_bounds() {
+	test_eval_fail("⎕RL←0", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	test_eval_fail("⎕RL←2147483647", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+}
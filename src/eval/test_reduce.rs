@@ -0,0 +1,58 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_reduce_vector() {
+	test_eval_display("+/⍳5", "15");
+	test_eval_display("×/⍳5", "120");
+	//Reduction goes from the right
+	test_eval_display("-/1 2 3", "2");
+	test_eval_display("*/2 3 2", "512");
+	test_eval_display("⌈/3 1 4", "4");
+	test_eval_display("+/1.5 2", "3.5");
+	test_eval_display("+/5", "5");
+	test_eval_display(",/1 2 3", " 1 2 3");
+	test_eval_display("+/(1 2)(3 4)", " 4 6");
+}
+
+#[test]
+fn test_reduce_identity() {
+	test_eval_display("+/⍳0", "0");
+	test_eval_display("-/⍳0", "0");
+	test_eval_display("×/⍳0", "1");
+	test_eval_display("÷/⍳0", "1");
+	test_eval_display("*/⍳0", "1");
+	test_eval_display("∧/⍳0", "1");
+	test_eval_display("∨/⍳0", "0");
+	test_eval_display("=/⍳0", "1");
+	test_eval_display("</⍳0", "0");
+	test_eval_display("(⌈/⍳0)=-⌊/⍳0", "1");
+	test_eval_display("0>⌈/⍳0", "1");
+	test_eval_fail("○/⍳0", |error| {
+		assert_eq!(error, AplError::domain("no identity for reduction of an empty array").at_position(1, 2));
+	});
+	//An empty axis gives the identity for every other position
+	test_eval_display("+/3 0⍴0", "0 0 0");
+	test_eval_display("⍴+/0 3⍴0", "0");
+}
+
+#[test]
+fn test_reduce_axis() {
+	test_eval_display("+/2 3⍴⍳6", "6 15");
+	test_eval_display("+⌿2 3⍴⍳6", "5 7 9");
+	test_eval_display("+/[1]2 3⍴⍳6", "5 7 9");
+	test_eval_display("+⌿[2]2 3⍴⍳6", "6 15");
+	test_eval_display("+/2 2 2⍴⍳8", " 3  7\n11 15");
+	test_eval_display("+⌿2 2 2⍴⍳8", " 6  8\n10 12");
+	test_eval_display("⎕IO←0 ⋄ +/[0]2 3⍴⍳6", "3 5 7");
+	for input in ["+/[3]2 3⍴⍳6", "+/[0]2 3⍴⍳6", "+/[1.5]2 3⍴⍳6"] {
+		test_eval_fail(input, |error| {
+			assert!(matches!(error, AplError::Axis(_, _)), "{}", input);
+		});
+	}
+}
@@ -0,0 +1,15 @@
+use crate::eval::test_eval::test_eval_display;
+
+#[test]
+fn test_reshape_basic() {
+	test_eval_display("2 3⍴⍳6", "1 2 3\n4 5 6");
+	test_eval_display("5⍴1 2", "1 2 1 2 1");
+	test_eval_display("⍴0⍴'abc'", "0");
+}
+
+#[test]
+fn test_reshape_empty_fill() {
+	test_eval_display("2 3⍴⍬", "0 0 0\n0 0 0");
+	test_eval_display("(2 3⍴'')=' '", "1 1 1\n1 1 1");
+	test_eval_display("'[',(3⍴0⍴'abc'),']'", "[   ]");
+}
@@ -0,0 +1,51 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_residue_negative() {
+	//The result takes the sign of the left argument
+	test_eval_display("3|¯7 7", "2 1");
+	test_eval_display("¯3|7 ¯7", "-2 -1");
+	test_eval_display("0|¯7", "-7");
+	test_eval_display("¯1|¯9223372036854775807-1", "0");
+}
+
+#[test]
+fn test_residue_float() {
+	test_eval_display("1|2.5 ¯0.25", "0.5 0.75");
+	test_eval_display("2.5|7", "2");
+	test_eval_display("¯2.5|7", "-0.5");
+}
+
+#[test]
+fn test_residue_complex() {
+	test_eval_display("1J1|2J3", "-1J0");
+	test_eval_display("2J1|5J5", "0J0");
+}
+
+#[test]
+fn test_residue_tolerance() {
+	//A quotient tolerantly equal to a whole number leaves nothing
+	test_eval_display("0.1|0.3", "0");
+	test_eval_display("3|9.000000000000002", "0");
+	test_eval_display("⎕CT←0 ⋄ 0.1|0.3", "0.09999999999999998");
+	//But a quotient that is merely small is not taken for 0
+	test_eval_display("(2⋆1000)|3", "3");
+}
+
+#[test]
+fn test_residue_infinite() {
+	test_eval_display("x←2⋆2000 ⋄ x|3 0", "3 0");
+	test_eval_display("x←2⋆2000 ⋄ x|¯3", "inf");
+	test_eval_display("x←2⋆2000 ⋄ (-x)|¯3 3", "-3 -inf");
+	for input in ["x←2⋆2000 ⋄ 3|x", "x←2⋆2000 ⋄ x|x", "x←2⋆2000 ⋄ x|3J1"] {
+		test_eval_fail(input, |error| {
+			assert!(matches!(error, AplError::Domain(_, _)), "{}", error);
+		});
+	}
+}
@@ -0,0 +1,60 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_reverse() {
+	test_eval_display("⌽1 2 3", "3 2 1");
+	test_eval_display("⌽'abc'", "cba");
+	test_eval_display("⌽5", "5");
+	//⌽ works along the last axis and ⊖ along the first
+	test_eval_display("⌽2 3⍴⍳6", "3 2 1\n6 5 4");
+	test_eval_display("⊖2 3⍴⍳6", "4 5 6\n1 2 3");
+	test_eval_display("⌽[1]2 3⍴⍳6", "4 5 6\n1 2 3");
+	test_eval_display("⊖[2]2 3⍴⍳6", "3 2 1\n6 5 4");
+	test_eval_fail("⌽[3]2 3⍴⍳6", |error| {
+		assert!(matches!(error, AplError::Axis(_, _)));
+	});
+}
+
+#[test]
+fn test_rotate() {
+	test_eval_display("1⌽1 2 3", "2 3 1");
+	test_eval_display("¯1⌽1 2 3", "3 1 2");
+	test_eval_display("4⌽1 2 3", "2 3 1");
+	test_eval_display("1⌽2 3⍴⍳6", "2 3 1\n5 6 4");
+	test_eval_display("1⊖2 3⍴⍳6", "4 5 6\n1 2 3");
+	test_eval_display("1⌽[1]2 3⍴⍳6", "4 5 6\n1 2 3");
+}
+
+#[test]
+fn test_rotate_each_line() {
+	//A count for each row, or for each column
+	test_eval_display("1 2⌽2 3⍴⍳6", "2 3 1\n6 4 5");
+	test_eval_display("1 ¯1 0⊖2 3⍴⍳6", "4 5 3\n1 2 6");
+	test_eval_display("(2 2⍴1 0 0 1)⌽[2]2 2 2⍴⍳8", "3 2\n1 4\n\n5 8\n7 6");
+	test_eval_fail("1 2⌽1 2 3", |error| {
+		assert!(matches!(error, AplError::Rank(_, _)));
+	});
+	test_eval_fail("1 2 3⌽2 3⍴⍳6", |error| {
+		assert!(matches!(error, AplError::Length(_, _)));
+	});
+}
+
+#[test]
+fn test_rotate_large_counts() {
+	test_eval_display("9223372036854775807⌽1 2 3", "2 3 1");
+	test_eval_display("¯9223372036854775807⌽1 2 3", "3 1 2");
+	test_eval_display("9223372036854775807 ¯9223372036854775807⌽2 3⍴⍳6", "2 3 1\n6 4 5");
+}
+
+#[test]
+fn test_rotate_empty_fill() {
+	test_eval_display("'[',(3↑⌽''),']'", "[   ]");
+	test_eval_display("'[',(3↑1⌽''),']'", "[   ]");
+	test_eval_display("'[',(3↑⊖0 2⍴''),']'", "[  ]\n[  ]\n[  ]");
+}
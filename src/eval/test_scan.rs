@@ -0,0 +1,30 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_scan_vector() {
+	test_eval_display("+\\⍳5", "1 3 6 10 15");
+	test_eval_display("×\\⍳5", "1 2 6 24 120");
+	//Each item is the reduction of the items up to it
+	test_eval_display("-\\1 2 3", "1 -1 2");
+	test_eval_display("⌈\\3 1 4 1 5", "3 3 4 4 5");
+	test_eval_display("∧\\1 1 0 1", "1 1 0 0");
+	test_eval_display("+\\5", "5");
+	test_eval_display("⍴+\\⍳0", "0");
+}
+
+#[test]
+fn test_scan_axis() {
+	test_eval_display("+\\2 3⍴⍳6", "1 3  6\n4 9 15");
+	test_eval_display("+⍀2 3⍴⍳6", "1 2 3\n5 7 9");
+	test_eval_display("+\\[1]2 3⍴⍳6", "1 2 3\n5 7 9");
+	test_eval_display("+⍀[2]2 3⍴⍳6", "1 3  6\n4 9 15");
+	test_eval_fail("+\\[3]2 3⍴⍳6", |error| {
+		assert!(matches!(error, AplError::Axis(_, _)));
+	});
+}
@@ -0,0 +1,37 @@
+use crate::eval::test_eval::test_eval_display;
+
+#[test]
+fn test_take_basic() {
+	test_eval_display("2↑1 2 3", "1 2");
+	test_eval_display("¯2↑1 2 3", "2 3");
+	test_eval_display("5↑1 2 3", "1 2 3 0 0");
+	test_eval_display("'[',(5↑'abc'),']'", "[abc  ]");
+	test_eval_display("1↓1 2 3", "2 3");
+	test_eval_display("¯1↓'abc'", "ab");
+}
+
+#[test]
+fn test_take_empty_fill() {
+	//An empty character vector is still padded with spaces
+	test_eval_display("'[',(4↑''),']'", "[    ]");
+	test_eval_display("'[',(3↑0↑'abc'),']'", "[   ]");
+	test_eval_display("'[',(2↑3↓'abc'),']'", "[  ]");
+	test_eval_display("'[',(2↑'',''),']'", "[  ]");
+	test_eval_display("'[',(↑''),']'", "[ ]");
+	//And an empty numeric vector with zeros
+	test_eval_display("3↑⍬", "0 0 0");
+	test_eval_display("3↑0↑1 2", "0 0 0");
+	//The fill of an empty nested array keeps the structure of its items
+	test_eval_display("⍴↑1↑0⍴⊂1 2", "2");
+}
+
+#[test]
+fn test_split_empty_fill() {
+	test_eval_display("↓2 3⍴⍳6", " 1 2 3  4 5 6");
+	test_eval_display("⍴↓3 0⍴''", "3");
+	//Each split vector pads with the argument's fill item
+	test_eval_display("'[',(3↑↑↓3 0⍴''),']'", "[   ]");
+	test_eval_display("3↑↑↓3 0⍴0", "0 0 0");
+	//And an empty split keeps a vector of them
+	test_eval_display("'[',(↑↓0 3⍴''),']'", "[   ]");
+}
@@ -0,0 +1,44 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_transpose() {
+	test_eval_display("⍉2 3⍴⍳6", "1 4\n2 5\n3 6");
+	test_eval_display("2 1⍉2 3⍴⍳6", "1 4\n2 5\n3 6");
+	test_eval_display("1 2⍉2 3⍴⍳6", "1 2 3\n4 5 6");
+	test_eval_display("⍴3 1 2⍉2 3 4⍴⍳24", "3 4 2");
+	test_eval_display("⍉1 2 3", "1 2 3");
+}
+
+#[test]
+fn test_transpose_diagonals() {
+	//Repeating an axis takes the diagonal, as long as the shorter of the two
+	test_eval_display("1 1⍉3 3⍴⍳9", "1 5 9");
+	test_eval_display("1 1⍉2 3⍴⍳6", "1 5");
+	test_eval_display("2 1 1⍉2 3 3⍴⍳18", "1 10\n5 14\n9 18");
+	test_eval_display("1 1 2⍉2 3 4⍴⍳24", " 1  2  3  4\n17 18 19 20");
+	test_eval_display("1 1 1⍉2 2 2⍴⍳8", "1 8");
+	test_eval_display("⎕IO←0 ⋄ 0 0⍉3 3⍴⍳9", "0 4 8");
+}
+
+#[test]
+fn test_transpose_errors() {
+	test_eval_fail("3 1⍉2 3⍴⍳6", |error| {
+		assert!(matches!(error, AplError::Domain(_, _)));
+	});
+	test_eval_fail("1⍉2 3⍴⍳6", |error| {
+		assert!(matches!(error, AplError::Length(_, _)));
+	});
+}
+
+#[test]
+fn test_transpose_empty_fill() {
+	test_eval_display("'[',(3↑⍉''),']'", "[   ]");
+	test_eval_display("⍴⍉0 2⍴''", "2 0");
+	test_eval_display("(2 3↑⍉0 2⍴'')=' '", "1 1 1\n1 1 1");
+}
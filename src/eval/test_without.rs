@@ -0,0 +1,33 @@
+use crate::{
+	error::AplError,
+	eval::test_eval::{
+		test_eval_display,
+		test_eval_fail,
+	},
+};
+
+#[test]
+fn test_without_basic() {
+	test_eval_display("1 2 3 4 5~2 4", "1 3 5");
+	test_eval_display("'hello'~'l'", "heo");
+	test_eval_display("1 2 3~'abc'", "1 2 3");
+	test_eval_display("1 2~⍬", "1 2");
+	test_eval_display("⍴⍬~1 2", "0");
+	test_eval_display("1 2 3~2", "1 3");
+	test_eval_display("(1 2)(3 4) 5~⊂3 4", " 1 2  5");
+	test_eval_fail("(2 2⍴⍳4)~1", |error| {
+		assert!(matches!(error, AplError::Rank(_, _)));
+	});
+}
+
+#[test]
+fn test_without_tolerance() {
+	test_eval_display("0.3 1~0.1+0.2", "1");
+	test_eval_display("⎕CT←0 ⋄ 0.3 1~0.1+0.2", "0.3 1");
+}
+
+#[test]
+fn test_without_empty_fill() {
+	test_eval_display("'[',(5↑'abc'~'abc'),']'", "[     ]");
+	test_eval_display("5↑1 2~1 2", "0 0 0 0 0");
+}
@@ -0,0 +1,65 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			array_size,
+			filled_array,
+			integer_vector,
+			prototype,
+		},
+	},
+};
+
+//A⍉B moves axis i of B to position A[i] of the result. When several axes of B go to the
+//same place, the result runs along their diagonal.
+pub fn dyadic_transpose(first: &Value, other: &Value, origin: isize) -> Result<Box<Value>, AplError> {
+	let shape = other.shape();
+	let targets = integer_vector(first)?;
+	if targets.len() != shape.len() {
+		return Err(AplError::length(""))
+	}
+	let targets: Vec<usize> = targets.iter().map(|&target| {
+		usize::try_from(target - origin).map_err(|_| AplError::domain("expected an axis of the argument"))
+	}).collect::<Result<_, _>>()?;
+
+	//The result has one axis for each distinct target, and they must leave no gaps
+	let rank = targets.iter().map(|&target| target + 1).max().unwrap_or(0);
+	if (0..rank).any(|axis| !targets.contains(&axis)) {
+		return Err(AplError::domain("the axes must run from the index origin without gaps"))
+	}
+	let mut dimensions: Vec<usize> = vec![usize::MAX; rank];
+	for (axis, &target) in targets.iter().enumerate() {
+		dimensions[target] = dimensions[target].min(shape[axis]);
+	}
+	let size = array_size(&dimensions)?;
+
+	let values = match other {
+		Value::AplArray(_, values, _) => values.clone(),
+		_ => vec![Box::new(other.clone())]
+	};
+	let mut strides: Vec<usize> = vec![1; shape.len()];
+	for axis in (0..shape.len().saturating_sub(1)).rev() {
+		strides[axis] = strides[axis + 1] * shape[axis + 1];
+	}
+
+	let mut result_values: Vec<Box<Value>> = Vec::with_capacity(size);
+	let mut index: Vec<usize> = vec![0; rank];
+	for position in 0..size {
+		let mut remainder = position;
+		for axis in (0..rank).rev() {
+			index[axis] = remainder % dimensions[axis];
+			remainder /= dimensions[axis];
+		}
+		let offset: usize = targets.iter().zip(strides.iter()).map(|(&target, stride)| index[target] * stride).sum();
+		result_values.push(values[offset].clone());
+	}
+	Ok(filled_array(dimensions, result_values, &prototype(other)))
+}
+
+//⍉B reverses the order of the axes
+pub fn transpose(first: &Value) -> Result<Box<Value>, AplError> {
+	let rank = first.rank();
+	let targets: Vec<Box<Value>> = (0..rank).rev().map(|axis| Box::new(Value::AplInteger(axis as isize))).collect();
+	dyadic_transpose(&Value::AplArray(vec![rank], targets, None), first, 0)
+}
@@ -0,0 +1,26 @@
+use crate::{
+	error::AplError,
+	eval::{
+		eval::Value,
+		array_helpers::{
+			filled_array,
+			prototype,
+			ravel_list,
+			values_match,
+		},
+	},
+};
+
+//The items of the left argument that don't occur anywhere in the right, in their order.
+//Items are compared as = compares them, within ⎕CT.
+pub fn without(first: &Value, other: &Value, tolerance: f64) -> Result<Box<Value>, AplError> {
+	if first.rank() > 1 {
+		return Err(AplError::rank(""))
+	}
+	let excluded = ravel_list(other);
+
+	let contents: Vec<Box<Value>> = ravel_list(first).into_iter()
+		.filter(|item| !excluded.iter().any(|other_item| values_match(item, other_item, tolerance)))
+		.collect();
+	Ok(filled_array(vec![contents.len()], contents, &prototype(first)))
+}
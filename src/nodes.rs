@@ -1,115 +1,489 @@
+use std::{
+	cell::RefCell,
+	rc::Rc,
+};
 use crate::{
+	error::AplError,
 	tokenizer::{
 		Token,
 		TokenData,
 	},
 	parser::Parser,
 	eval::{
-		eval::Value,
-		add::eval_addition,
-		subtract::eval_subtraction,
-		multiply::eval_multiplication,
-		divide::eval_division,
-		maximum::eval_maximum,
-		minimum::eval_minimum,
-		exponential::eval_exponential,
-		conjugate::eval_conjugate,
-		negate::eval_negate,
-		reciprocal::eval_reciprocal,
-		sign::eval_sign,
-		magnitude::eval_magnitude,
-		ceiling::eval_ceiling,
-		floor::eval_floor,
-		power::eval_power,
+		eval::{
+			Value,
+			eval_node,
+		},
+		array_helpers::{
+			axis_index,
+			axis_list,
+			axis_number,
+		},
+		environment::Environment,
+		assignment::eval_assignment,
+		add::add,
+		subtract::subtract,
+		multiply::multiply,
+		divide::divide,
+		maximum::maximum,
+		minimum::minimum,
+		exponential::exponential,
+		logarithm::{
+			natural_log,
+			logarithm,
+		},
+		conjugate::conjugate,
+		negate::negate,
+		reciprocal::reciprocal,
+		sign::sign,
+		magnitude::magnitude,
+		residue::residue,
+		ceiling::ceiling,
+		floor::floor,
+		power::power,
+		circle::{
+			pi_times,
+			circle,
+		},
+		factorial::{
+			factorial,
+			binomial,
+		},
+		shape::shape,
+		reshape::reshape,
+		index_generator::index_generator,
+		index_of::index_of,
+		random::{
+			with_random_link,
+			roll,
+			deal,
+		},
+		reduce::reduce,
+		scan::scan,
+		outer_product::outer_product,
+		inner_product::inner_product,
+		each::{
+			each_monadic,
+			each_dyadic,
+		},
+		catenate::{
+			catenate,
+			catenate_axis,
+			ravel,
+			ravel_axis,
+			table,
+		},
+		take::{
+			take,
+			first,
+		},
+		drop::{
+			drop,
+			split,
+		},
+		rotate::{
+			reverse,
+			rotate,
+		},
+		transpose::{
+			transpose,
+			dyadic_transpose,
+		},
+		grade::{
+			grade_up,
+			grade_down,
+			collated_grade_up,
+			collated_grade_down,
+		},
+		enclose::enclose,
+		disclose::disclose,
+		tally::tally,
+		dfn::call_dfn,
+		comparison::{
+			less,
+			less_or_equal,
+			equal,
+			greater_or_equal,
+			greater,
+			not_equal,
+		},
+		logical::{
+			and,
+			or,
+			nand,
+			nor,
+			not,
+		},
+		without::without,
 	},
 };
 
 pub trait EvalNode {
-	fn eval(&self) -> Result<Box<Value>, String>;
+	fn eval(&self, env: &mut Environment) -> Result<Box<Value>, AplError>;
 }
 
 pub trait Parseable {
-	fn monadic(&self, parser: &mut Parser) -> Result<Box<Node>, String>;
-	fn dyadic(&self, parser: &mut Parser, left: Box<Node>) -> Result<Box<Node>, String>;
+	fn monadic(&self, parser: &mut Parser) -> Result<Box<Node>, AplError>;
+	fn dyadic(&self, parser: &mut Parser, left: Box<Node>) -> Result<Box<Node>, AplError>;
 }
 
 impl Parseable for TokenData {
-	fn monadic(&self, parser: &mut Parser) -> Result<Box<Node>, String> {
-		match self.string.as_str() {
-			"+" => parser.create_monadic_result(Node::Conjugate),
-			"-" | "−" => parser.create_monadic_result(Node::Negate),
-			"×" => parser.create_monadic_result(Node::Sign),
-			"÷" => parser.create_monadic_result(Node::Reciprocal),
-			"|" | "∣" => parser.create_monadic_result(Node::Magnitude),
-			"⌈" => parser.create_monadic_result(Node::Ceiling),
-			"⌊" => parser.create_monadic_result(Node::Floor),
-			"⋆" | "*" => parser.create_monadic_result(Node::Exponential),
-			_ => parser.parse_base_expression()
+	fn monadic(&self, parser: &mut Parser) -> Result<Box<Node>, AplError> {
+		if starts_function(&self.string) {
+			parser.parse_function().and_then(|function| parser.create_monadic_result(function))
+		} else {
+			parser.parse_base_expression()
 		}
 	}
 
-	fn dyadic(&self, parser: &mut Parser, left: Box<Node>) -> Result<Box<Node>, String> {
+	fn dyadic(&self, parser: &mut Parser, left: Box<Node>) -> Result<Box<Node>, AplError> {
 		match self.string.as_str() {
-			"+" => parser.create_dyadic_result(left, Node::Addition),
-			"-" | "−" => parser.create_dyadic_result(left, Node::Subtraction),
-			"×" => parser.create_dyadic_result(left, Node::Multiplication),
-			"÷" => parser.create_dyadic_result(left, Node::Division),
-			"⌈" => parser.create_dyadic_result(left, Node::Maximum),
-			"⌊" => parser.create_dyadic_result(left, Node::Minimum),
-			"⋆" | "*" => parser.create_dyadic_result(left, Node::Power),
-			_ => Err("Unknown operator".to_string())
+			"←" => parser.create_assignment(left),
+			glyph if starts_function(glyph) => {
+				parser.parse_function().and_then(|function| parser.create_dyadic_result(left, function))
+			},
+			_ => Err(parser.syntax_error("unknown operator"))
 		}
 	}
 }
 
+pub fn is_primitive_function(glyph: &str) -> bool {
+	matches!(glyph, "+" | "-" | "−" | "×" | "÷" | "|" | "∣" | "⌈" | "⌊" | "⋆" | "*" | "⍟" | "○" | "!" | "⍴" | "," | "⍪" | "⍳" | "?" | "↑" | "↓" | "⌽" | "⊖" | "⍉" | "⍋" | "⍒" | "⊂" | "⊃" | "≢" |
+		"<" | "≤" | "=" | "≥" | ">" | "≠" | "∧" | "∨" | "⍲" | "⍱" | "~")
+}
+
+pub fn is_monadic_operator(glyph: &str) -> bool {
+	matches!(glyph, "/" | "⌿" | "\\" | "⍀" | "¨")
+}
+
+//Outer product is written before its operand, so it begins a function too, as do
+//dfn literals and ∇ inside them
+pub fn starts_function(glyph: &str) -> bool {
+	is_primitive_function(glyph) || matches!(glyph, "∘." | "{" | "∇")
+}
+
+fn glyph(token: &Token) -> &str {
+	match token.token_data() {
+		Some(token_data) => token_data.string.as_str(),
+		None => ""
+	}
+}
+
+#[derive(Clone, Debug)]
+pub enum Function {
+	//A primitive function, named by its glyph
+	Primitive(Box<Token>),
+	//A monadic operator applied to its function operand, as in +/ or ∘.×
+	Derived(Box<Token>, Box<Function>),
+	//A dyadic operator applied to its left and right function operands, as in +.×
+	DerivedDyadic(Box<Token>, Box<Function>, Box<Function>),
+	//A function bound to a name, or ∇ for the dfn being run
+	Named(Box<Token>),
+	//A dfn literal and its body
+	Dfn(Box<Token>, Rc<DfnBody>),
+	//A function given an axis in brackets, as in ,[0.5]
+	Axis(Box<Token>, Box<Function>, Box<Node>),
+}
+
+//Whether a name holds a function decides how the body of a dfn parses, and that can change
+//after the dfn is defined, as when it calls a function defined later. The tokens from { to }
+//are kept so the body can be parsed again when it does.
 #[derive(Debug)]
+pub struct DfnBody {
+	pub tokens: Vec<Token>,
+	//The names in the body that were functions when it was last parsed, and its statements
+	pub parsed: RefCell<(Vec<String>, Statements)>
+}
+
+pub type Statements = Rc<Vec<Box<Node>>>;
+
+impl DfnBody {
+	pub fn new(tokens: Vec<Token>, is_function: impl Fn(&str) -> bool, statements: Statements) -> DfnBody {
+		let functions = DfnBody::function_names(&tokens, is_function);
+		DfnBody {
+			tokens,
+			parsed: RefCell::new((functions, statements))
+		}
+	}
+
+	//The names in a body that are functions, sorted so that two lists can be compared
+	pub fn function_names(tokens: &[Token], is_function: impl Fn(&str) -> bool) -> Vec<String> {
+		let mut names: Vec<String> = tokens.iter().filter_map(|token| {
+			match token {
+				Token::Variable(token_data) if is_function(&token_data.string) => Some(token_data.string.clone()),
+				_ => None
+			}
+		}).collect();
+		names.sort();
+		names.dedup();
+		names
+	}
+}
+
+impl Function {
+	pub fn apply_monadic(&self, env: &mut Environment, right: &Value) -> Result<Box<Value>, AplError> {
+		self.call_monadic(env, right).map_err(|error| self.locate(error))
+	}
+
+	pub fn apply_dyadic(&self, env: &mut Environment, left: &Value, right: &Value) -> Result<Box<Value>, AplError> {
+		self.call_dyadic(env, left, right).map_err(|error| self.locate(error))
+	}
+
+	//Errors point at the innermost primitive that raised them. A named function's body may
+	//come from an earlier line, so its errors point at the name instead.
+	fn locate(&self, mut error: AplError) -> AplError {
+		match self {
+			Function::Primitive(token) | Function::Derived(token, _) |
+			Function::DerivedDyadic(token, _, _) | Function::Dfn(token, _) => error.at(token),
+			Function::Named(token) => {
+				error.relocate(token);
+				error
+			},
+			Function::Axis(_, function, _) => function.locate(error)
+		}
+	}
+
+	fn call_monadic(&self, env: &mut Environment, right: &Value) -> Result<Box<Value>, AplError> {
+		match self {
+			Function::Primitive(token) => {
+				match glyph(token) {
+					"+" => conjugate(right),
+					"-" | "−" => negate(right),
+					"×" => sign(right),
+					"÷" => reciprocal(right),
+					"|" | "∣" => magnitude(right),
+					"⌈" => ceiling(right),
+					"⌊" => floor(right),
+					"⋆" | "*" => exponential(right),
+					"⍟" => natural_log(right, env.complex_results()),
+					"○" => pi_times(right),
+					"!" => factorial(right),
+					"⍴" => shape(right),
+					"," => ravel(right),
+					"⍪" => table(right),
+					"↑" => first(right),
+					"↓" => split(right, right.rank().max(1) - 1),
+					"⌽" => reverse(right, right.rank().max(1) - 1),
+					"⊖" => reverse(right, 0),
+					"⍉" => transpose(right),
+					"⍋" => grade_up(right, env.index_origin()),
+					"⍒" => grade_down(right, env.index_origin()),
+					"⍳" => index_generator(right, env.index_origin()),
+					"?" => {
+						let origin = env.index_origin();
+						with_random_link(env, |link| roll(right, link, origin))
+					},
+					"⊂" => enclose(right),
+					"⊃" => disclose(right),
+					"≢" => tally(right),
+					"~" => not(right),
+					_ => Err(AplError::valence(""))
+				}
+			},
+			Function::Derived(operator, operand) => {
+				let last_axis = right.rank().max(1) - 1;
+				match glyph(operator) {
+					"/" => reduce(|l, r| operand.apply_dyadic(env, l, r), operand.identity(), right, last_axis),
+					"⌿" => reduce(|l, r| operand.apply_dyadic(env, l, r), operand.identity(), right, 0),
+					"\\" => scan(|l, r| operand.apply_dyadic(env, l, r), operand.is_associative(), right, last_axis),
+					"⍀" => scan(|l, r| operand.apply_dyadic(env, l, r), operand.is_associative(), right, 0),
+					"¨" => each_monadic(|r| operand.apply_monadic(env, r), right),
+					"∘." => Err(AplError::valence("")),
+					_ => Err(AplError::syntax("unknown operator"))
+				}
+			},
+			Function::DerivedDyadic(_, _, _) => Err(AplError::valence("")),
+			Function::Named(token) => {
+				match env.get_function(glyph(token)) {
+					Some((function, mut definition)) => function.apply_monadic(&mut definition, right),
+					None => Err(AplError::value(format!("{} is not defined", glyph(token))))
+				}
+			},
+			Function::Dfn(_, body) => call_dfn(self, body, env, None, right),
+			Function::Axis(_, function, axis) => {
+				let axis = eval_node(axis, env)?;
+				function.call_monadic_axis(env, &axis, right)
+			}
+		}
+	}
+
+	//The functions that take an axis, with the axis evaluated already
+	fn call_monadic_axis(&self, env: &mut Environment, axis: &Value, right: &Value) -> Result<Box<Value>, AplError> {
+		let origin = env.index_origin();
+		match self {
+			Function::Primitive(token) => {
+				match glyph(token) {
+					"," => ravel_axis(right, &axis_list(axis, origin)?),
+					"↓" => split(right, axis_index(axis, origin, right.rank().max(1))?),
+					"⌽" | "⊖" => reverse(right, axis_index(axis, origin, right.rank().max(1))?),
+					_ => Err(AplError::axis("this function takes no axis"))
+				}
+			},
+			Function::Derived(operator, operand) => {
+				let axis = axis_index(axis, origin, right.rank().max(1))?;
+				match glyph(operator) {
+					"/" | "⌿" => reduce(|l, r| operand.apply_dyadic(env, l, r), operand.identity(), right, axis),
+					"\\" | "⍀" => scan(|l, r| operand.apply_dyadic(env, l, r), operand.is_associative(), right, axis),
+					_ => Err(AplError::axis("this function takes no axis"))
+				}
+			},
+			_ => Err(AplError::axis("this function takes no axis"))
+		}
+	}
+
+	fn call_dyadic(&self, env: &mut Environment, left: &Value, right: &Value) -> Result<Box<Value>, AplError> {
+		match self {
+			Function::Primitive(token) => {
+				match glyph(token) {
+					"+" => add(left, right),
+					"-" | "−" => subtract(left, right),
+					"×" => multiply(left, right),
+					"÷" => divide(left, right),
+					"|" | "∣" => residue(left, right, env.comparison_tolerance()),
+					"⌈" => maximum(left, right),
+					"⌊" => minimum(left, right),
+					"⋆" | "*" => power(left, right, env.complex_results()),
+					"⍟" => logarithm(left, right, env.complex_results()),
+					"○" => circle(left, right),
+					"!" => binomial(left, right),
+					"⍴" => reshape(left, right),
+					"," => catenate(left, right, left.rank().max(right.rank()).max(1) - 1),
+					"⍪" => catenate(left, right, 0),
+					"↑" => take(left, right),
+					"↓" => drop(left, right),
+					"⌽" => rotate(left, right, right.rank().max(1) - 1),
+					"⊖" => rotate(left, right, 0),
+					"⍉" => dyadic_transpose(left, right, env.index_origin()),
+					"⍋" => collated_grade_up(left, right, env.index_origin()),
+					"⍒" => collated_grade_down(left, right, env.index_origin()),
+					"⍳" => index_of(left, right, env.index_origin(), env.comparison_tolerance()),
+					"?" => {
+						let origin = env.index_origin();
+						with_random_link(env, |link| deal(left, right, link, origin))
+					},
+					"<" => less(left, right, env.comparison_tolerance()),
+					"≤" => less_or_equal(left, right, env.comparison_tolerance()),
+					"=" => equal(left, right, env.comparison_tolerance()),
+					"≥" => greater_or_equal(left, right, env.comparison_tolerance()),
+					">" => greater(left, right, env.comparison_tolerance()),
+					"≠" => not_equal(left, right, env.comparison_tolerance()),
+					"∧" => and(left, right, env.comparison_tolerance()),
+					"∨" => or(left, right, env.comparison_tolerance()),
+					"⍲" => nand(left, right),
+					"⍱" => nor(left, right),
+					"~" => without(left, right, env.comparison_tolerance()),
+					_ => Err(AplError::valence(""))
+				}
+			},
+			Function::Derived(operator, operand) => {
+				match glyph(operator) {
+					"∘." => outer_product(|l, r| operand.apply_dyadic(env, l, r), left, right),
+					"¨" => each_dyadic(|l, r| operand.apply_dyadic(env, l, r), left, right),
+					"/" | "⌿" | "\\" | "⍀" => Err(AplError::valence("")),
+					_ => Err(AplError::syntax("unknown operator"))
+				}
+			},
+			Function::DerivedDyadic(operator, reduction, product) => {
+				match glyph(operator) {
+					"." => inner_product(reduction, product, env, left, right),
+					_ => Err(AplError::syntax("unknown operator"))
+				}
+			},
+			Function::Named(token) => {
+				match env.get_function(glyph(token)) {
+					Some((function, mut definition)) => function.apply_dyadic(&mut definition, left, right),
+					None => Err(AplError::value(format!("{} is not defined", glyph(token))))
+				}
+			},
+			Function::Dfn(_, body) => call_dfn(self, body, env, Some(left), right),
+			Function::Axis(_, function, axis) => {
+				let axis = eval_node(axis, env)?;
+				function.call_dyadic_axis(env, &axis, left, right)
+			}
+		}
+	}
+
+	fn call_dyadic_axis(&self, env: &mut Environment, axis: &Value, left: &Value, right: &Value) -> Result<Box<Value>, AplError> {
+		let origin = env.index_origin();
+		match self {
+			Function::Primitive(token) => {
+				match glyph(token) {
+					"," | "⍪" => catenate_axis(left, right, axis_number(axis, origin)?),
+					"⌽" | "⊖" => rotate(left, right, axis_index(axis, origin, right.rank().max(1))?),
+					_ => Err(AplError::axis("this function takes no axis"))
+				}
+			},
+			_ => Err(AplError::axis("this function takes no axis"))
+		}
+	}
+
+	pub fn is_primitive(&self, name: &str) -> bool {
+		match self {
+			Function::Primitive(token) => glyph(token) == name,
+			_ => false
+		}
+	}
+
+	//The value a reduction over an empty axis gives
+	pub fn identity(&self) -> Option<Value> {
+		match self {
+			Function::Primitive(token) => {
+				match glyph(token) {
+					"+" | "-" | "−" | "<" | ">" | "≠" | "∨" => Some(Value::AplInteger(0)),
+					"×" | "÷" | "⋆" | "*" | "≤" | "=" | "≥" | "∧" => Some(Value::AplInteger(1)),
+					"⌈" => Some(Value::AplFloat(f64::MIN)),
+					"⌊" => Some(Value::AplFloat(f64::MAX)),
+					_ => None
+				}
+			},
+			_ => None
+		}
+	}
+
+	pub fn is_associative(&self) -> bool {
+		match self {
+			Function::Primitive(token) => matches!(glyph(token), "+" | "×" | "⌈" | "⌊" | "∧" | "∨"),
+			_ => false
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
 pub enum Node {
-	//Dyadic
-	Addition(Box<Token>, Box<Node>, Box<Node>),
-	Subtraction(Box<Token>, Box<Node>, Box<Node>),
-	Multiplication(Box<Token>, Box<Node>, Box<Node>),
-	Division(Box<Token>, Box<Node>, Box<Node>),
-	Maximum(Box<Token>, Box<Node>, Box<Node>),
-	Minimum(Box<Token>, Box<Node>, Box<Node>),
-	Power(Box<Token>, Box<Node>, Box<Node>),
-
-	//Monadic
-	Conjugate(Box<Token>, Box<Node>),
-	Negate(Box<Token>, Box<Node>),
-	Reciprocal(Box<Token>, Box<Node>),
-	Sign(Box<Token>, Box<Node>),
-	Magnitude(Box<Token>, Box<Node>),
-	Ceiling(Box<Token>, Box<Node>),
-	Floor(Box<Token>, Box<Node>),
-	Exponential(Box<Token>, Box<Node>),
+	Assignment(Box<Token>, Box<Node>, Box<Node>),
+	FunctionAssignment(Box<Token>, Box<Node>, Box<Function>),
+	//A dfn statement of the form condition: result
+	Guard(Box<Token>, Box<Node>, Box<Node>),
+	Monadic(Box<Function>, Box<Node>),
+	Dyadic(Box<Function>, Box<Node>, Box<Node>),
 
 	//Niladic
 	Variable(Box<Token>),
 	Array(Vec<Box<Token>>),
+	Strand(Vec<Box<Node>>),
 	Zilde(Box<Token>),
 }
 
 impl EvalNode for Node {
-	fn eval(&self) -> Result<Box<Value>, String> {
+	fn eval(&self, env: &mut Environment) -> Result<Box<Value>, AplError> {
 		match self {
-			Node::Addition(_, left, right) => eval_addition(left, right),
-			Node::Subtraction(_, left, right) => eval_subtraction(left, right),
-			Node::Multiplication(_, left, right) => eval_multiplication(left, right),
-			Node::Division(_, left, right) => eval_division(left, right),
-			Node::Maximum(_, left, right) => eval_maximum(left, right),
-			Node::Minimum(_, left, right) => eval_minimum(left, right),
-			Node::Power(_, left, right) => eval_power(left, right),
-
-			Node::Conjugate(_, left) => eval_conjugate(left),
-			Node::Negate(_, left) => eval_negate(left),
-			Node::Reciprocal(_, left) => eval_reciprocal(left),
-			Node::Sign(_, left) => eval_sign(left),
-			Node::Magnitude(_, left) => eval_magnitude(left),
-			Node::Ceiling(_, left) => eval_ceiling(left),
-			Node::Floor(_, left) => eval_floor(left),
-			Node::Exponential(_, left) => eval_exponential(left),
-
-			_ => Err("Not yet implemented".to_string())
+			Node::Assignment(arrow, target, value) => {
+				eval_assignment(target, value, env).map_err(|error| error.at(arrow))
+			},
+			Node::Monadic(function, right) => {
+				let right = eval_node(right, env)?;
+				function.apply_monadic(env, &right)
+			},
+			Node::Dyadic(function, left, right) => {
+				//The right argument is evaluated first, so X←2 on the right is visible on the left
+				let right = eval_node(right, env)?;
+				let left = eval_node(left, env)?;
+				function.apply_dyadic(env, &left, &right)
+			},
+			Node::FunctionAssignment(token, _, _) | Node::Guard(token, _, _) => {
+				Err(AplError::syntax("this statement has no value").at(token))
+			},
+			_ => Err(AplError::nonce("not yet implemented"))
 		}
 	}
 }
@@ -1,51 +1,120 @@
+use std::{
+	collections::{
+		HashSet,
+		VecDeque,
+	},
+	rc::Rc,
+};
 use crate::{
+	error::AplError,
 	tokenizer::{
 		Token,
 		Tokenizer,
 	},
 	nodes::{
 		Node,
+		Function,
+		DfnBody,
+		Statements,
 		Parseable,
+		is_monadic_operator,
+		is_primitive_function,
+		starts_function,
 	},
 };
 
 pub struct Parser {
 	tokenizer: Box<Tokenizer>,
-	current_token: Option<Box<Token>>
+	current_token: Option<Box<Token>>,
+	//Names known to hold functions, which decides whether F X is a call or a strand
+	functions: HashSet<String>,
+	//The tokens read so far by each dfn being parsed, innermost last
+	recordings: Vec<Vec<Token>>,
+	//Tokens to read before the tokenizer's, when a dfn is parsed again
+	replay: VecDeque<Token>,
+	//How many expressions the one being parsed is inside
+	nesting: usize
 }
 
+//Parentheses, dfns and the right arguments of functions each nest an expression inside
+//another, and parsing and evaluating them recurses. This keeps both well inside the stack
+//that evaluation keeps in reserve.
+const MAX_NESTING: usize = 128;
+
 impl Parser {
 	pub fn new(input_string: String) -> Parser {
 		Parser {
 			tokenizer: Box::new(Tokenizer::new(input_string)),
-			current_token: None
+			current_token: None,
+			functions: HashSet::new(),
+			recordings: vec![],
+			replay: VecDeque::new(),
+			nesting: 0
 		}
 	}
 
-	pub fn parse_next_statement(&mut self) -> Result<Box<Node>, String> {
-		match self.read_next_token() {
-			Ok(()) => {
-				match self.current_token.clone().map(|t| *t) {
-					Some(Token::EndOfFile) => {
-						Err("End of File".to_string())
-					},
-					Some(_) => {
-						self.parse_dyadic()
-					},
-					None => {
-						Err("Everything is wrong".to_string())
-					}
+	//Parses the recorded tokens of a dfn again, taking the given names to be functions
+	pub fn parse_dfn_again(tokens: Vec<Token>, functions: &[String]) -> Result<Statements, AplError> {
+		let mut parser = Parser::new(String::new());
+		parser.replay = tokens.into();
+		for name in functions.iter() {
+			parser.declare_function(name);
+		}
+		parser.read_next_token()?;
+		let (_, body) = parser.parse_dfn_body()?;
+		Ok(body.parsed.into_inner().1)
+	}
+
+	//Points a syntax error at the current token
+	pub fn syntax_error(&self, message: &str) -> AplError {
+		self.error_here(AplError::syntax(message))
+	}
+
+	fn error_here(&self, error: AplError) -> AplError {
+		match self.current_token.as_deref() {
+			Some(token) => error.at(token),
+			None => error
+		}
+	}
+
+	pub fn declare_function(&mut self, name: &str) {
+		self.functions.insert(name.to_string());
+	}
+
+	//Statements are separated by newlines and ⋄. Empty statements are skipped, and None
+	//means the source has run out.
+	pub fn parse_next_statement(&mut self) -> Result<Option<Box<Node>>, AplError> {
+		loop {
+			self.read_next_token()?;
+			match self.current_token.as_deref() {
+				Some(Token::EndOfFile) => {
+					return Ok(None)
+				},
+				Some(_) if self.token_is_separator() => {
+					continue
+				},
+				Some(_) => {
+					let node = self.parse_dyadic()?;
+					self.expect_end_of_statement()?;
+					return Ok(Some(node))
+				},
+				None => {
+					return Err(self.syntax_error("no token was read"))
 				}
-			},
-			Err(msg) => {
-				Err(msg)
 			}
 		}
 	}
 
-	fn read_next_token(&mut self) -> Result<(), String> {
-		match self.tokenizer.read_next_token() {
+	fn read_next_token(&mut self) -> Result<(), AplError> {
+		let token = match self.replay.pop_front() {
+			Some(token) => Ok(Box::new(token)),
+			None => self.tokenizer.read_next_token()
+		};
+		match token {
 			Ok(token) => {
+				for recording in self.recordings.iter_mut() {
+					recording.push((*token).clone());
+				}
 				self.current_token = Some(token);
 				Ok(())
 			},
@@ -60,17 +129,88 @@ impl Parser {
 		matches!(self.current_token.clone().map(|t| *t), None | Some(Token::EndOfFile))
 	}
 
-	fn token_is_number(&self) -> bool {
-		matches!(self.current_token.clone().map(|t| *t), Some(Token::Number(_)))
+	//Numbers and quoted strings are written straight into the source
+	fn token_is_literal(&self) -> bool {
+		matches!(self.current_token.as_deref(), Some(Token::Number(_)) | Some(Token::String(_)))
+	}
+
+	fn token_is_primitive(&self, primitive: &str) -> bool {
+		match self.current_token.as_deref() {
+			Some(Token::Primitive(token_data)) => token_data.string == primitive,
+			_ => false
+		}
+	}
+
+	fn token_is_monadic_operator(&self) -> bool {
+		match self.current_token.as_deref() {
+			Some(Token::Primitive(token_data)) => is_monadic_operator(&token_data.string),
+			_ => false
+		}
+	}
+
+	fn token_is_variable(&self) -> bool {
+		matches!(self.current_token.as_deref(), Some(Token::Variable(_)))
 	}
 
-	pub fn create_dyadic_result<F>(&mut self, left: Box<Node>, kind: F) -> Result<Box<Node>, String> where F: FnOnce(Box<Token>, Box<Node>, Box<Node>) -> Node, {
-		let stash = self.stash();
+	fn token_is_function_name(&self) -> bool {
+		match self.current_token.as_deref() {
+			Some(Token::Variable(token_data)) => self.functions.contains(&token_data.string),
+			_ => false
+		}
+	}
+
+	fn token_is_argument(&self) -> bool {
+		self.token_is_primitive("⍺") || self.token_is_primitive("⍵")
+	}
+
+	fn token_is_strand_item(&self) -> bool {
+		self.token_is_literal() || (self.token_is_variable() && !self.token_is_function_name()) ||
+			self.token_is_argument() || self.token_is_primitive("(")
+	}
+
+	fn token_starts_function(&self) -> bool {
+		match self.current_token.as_deref() {
+			Some(Token::Primitive(token_data)) => starts_function(&token_data.string),
+			_ => self.token_is_function_name()
+		}
+	}
+
+	fn token_is_separator(&self) -> bool {
+		matches!(self.current_token.as_deref(), Some(Token::Newline(_))) || self.token_is_primitive("⋄")
+	}
+
+	//Tokens that close off an expression without being part of it
+	fn token_ends_expression(&self) -> bool {
+		self.end_of_source() || self.token_is_separator() || self.token_is_primitive(")") ||
+			self.token_is_primitive("}") || self.token_is_primitive(":") || self.token_is_primitive("]")
+	}
+
+	fn expect_end_of_statement(&self) -> Result<(), AplError> {
+		match self.current_token.as_deref() {
+			None | Some(Token::EndOfFile) | Some(Token::Newline(_)) => Ok(()),
+			Some(Token::Primitive(token_data)) if token_data.string == "⋄" => Ok(()),
+			Some(Token::Primitive(token_data)) if token_data.string == ")" => {
+				Err(self.syntax_error("unmatched )"))
+			},
+			Some(_) => Err(self.syntax_error("unexpected token at end of statement"))
+		}
+	}
+
+	pub fn create_dyadic_result(&mut self, left: Box<Node>, function: Box<Function>) -> Result<Box<Node>, AplError> {
+		if self.token_is_primitive("←") {
+			//Modified assignment: X f← Y is X ← X f Y
+			if !is_assignable(&left) {
+				return Err(self.syntax_error("invalid assignment target"));
+			}
+			let arrow = self.stash()?;
+			let target = left.clone();
+			return self.parse_dyadic().map(|node| {
+				Box::new(Node::Assignment(arrow, target, Box::new(Node::Dyadic(function, left, node))))
+			});
+		}
 		match self.parse_dyadic() {
 			Ok(node) => {
-				let item = Box::new(kind(stash, left, node));
-				let _ = self.read_next_token();
-				Ok(item)
+				Ok(Box::new(Node::Dyadic(function, left, node)))
 			},
 			Err(msg) => {
 				Err(msg)
@@ -78,15 +218,181 @@ impl Parser {
 		}
 	}
 
-	fn parse_dyadic(&mut self) -> Result<Box<Node>, String> {
+	pub fn create_assignment(&mut self, target: Box<Node>) -> Result<Box<Node>, AplError> {
+		if !is_assignable(&target) {
+			return Err(self.syntax_error("invalid assignment target"));
+		}
+		let arrow = self.stash()?;
+
+		//F←{⍵} names a function, whereas X←-3 applies one
+		let value = if self.token_starts_function() {
+			let function = self.parse_function()?;
+			if self.token_ends_expression() {
+				return match *target {
+					Node::Variable(ref token) => {
+						if let Some(token_data) = token.token_data() {
+							self.functions.insert(token_data.string.clone());
+						}
+						Ok(Box::new(Node::FunctionAssignment(arrow, target, function)))
+					},
+					_ => Err(AplError::syntax("a function can only be assigned to one name").at(&arrow))
+				};
+			}
+			self.create_monadic_result(function)?
+		} else {
+			self.parse_dyadic()?
+		};
+
+		self.forget_functions(&target);
+		Ok(Box::new(Node::Assignment(arrow, target, value)))
+	}
+
+	fn forget_functions(&mut self, target: &Node) {
+		match target {
+			Node::Variable(token) => {
+				if let Some(token_data) = token.token_data() {
+					self.functions.remove(&token_data.string);
+				}
+			},
+			Node::Strand(items) => {
+				for item in items.iter() {
+					self.forget_functions(item);
+				}
+			},
+			_ => {}
+		}
+	}
+
+	//A primitive function followed by any operators that derive a new function from it
+	pub fn parse_function(&mut self) -> Result<Box<Function>, AplError> {
+		let mut function = if self.token_is_primitive("∘.") {
+			let operator = self.stash()?;
+			Box::new(Function::Derived(operator, self.parse_operand()?))
+		} else {
+			self.parse_operand()?
+		};
+
+		loop {
+			if self.token_is_monadic_operator() {
+				let operator = self.stash()?;
+				function = Box::new(Function::Derived(operator, function));
+			} else if self.token_is_primitive(".") {
+				let operator = self.stash()?;
+				function = Box::new(Function::DerivedDyadic(operator, function, self.parse_operand()?));
+			} else if self.token_is_primitive("[") {
+				function = self.parse_axis(function)?;
+			} else {
+				return Ok(function);
+			}
+		}
+	}
+
+	//An axis in brackets after a function, as in ,[0.5] or +/[1]
+	fn parse_axis(&mut self, function: Box<Function>) -> Result<Box<Function>, AplError> {
+		let open = self.stash()?;
+		if self.token_is_primitive("]") {
+			return Err(AplError::syntax("empty axis").at(&open));
+		}
 		if self.end_of_source() {
-			Err("Unexpected end of source".to_string())
+			return Err(AplError::syntax("unmatched [").at(&open));
+		}
+
+		let axis = self.parse_dyadic()?;
+		if self.token_is_primitive("]") {
+			self.read_next_token()?;
+			Ok(Box::new(Function::Axis(open, function, axis)))
+		} else {
+			Err(AplError::syntax("unmatched [").at(&open))
+		}
+	}
+
+	fn parse_operand(&mut self) -> Result<Box<Function>, AplError> {
+		if self.token_is_function_name() || self.token_is_primitive("∇") {
+			return Ok(Box::new(Function::Named(self.stash()?)));
+		}
+		match self.current_token.as_deref() {
+			Some(Token::Primitive(token_data)) if is_primitive_function(&token_data.string) => {
+				Ok(Box::new(Function::Primitive(self.stash()?)))
+			},
+			Some(Token::Primitive(token_data)) if token_data.string == "{" => {
+				self.parse_dfn()
+			},
+			_ => Err(self.syntax_error("expected a function operand"))
+		}
+	}
+
+	fn parse_dfn(&mut self) -> Result<Box<Function>, AplError> {
+		let (open, body) = self.parse_dfn_body()?;
+		Ok(Box::new(Function::Dfn(open, Rc::new(body))))
+	}
+
+	//Records every token from the { to the matching } while the statements are parsed, so
+	//that the body can be parsed again if a name in it changes class
+	fn parse_dfn_body(&mut self) -> Result<(Box<Token>, DfnBody), AplError> {
+		let known = self.functions.clone();
+		let depth = self.recordings.len();
+		self.recordings.push(self.current_token.iter().map(|token| (**token).clone()).collect());
+		let statements = self.parse_dfn_statements();
+		let tokens = self.recordings.split_off(depth).pop().unwrap_or_default();
+		let (open, statements) = statements?;
+		//The } is only passed once the recording has stopped, so it doesn't take in the
+		//token after it
+		self.stash()?;
+		Ok((open, DfnBody::new(tokens, |name| known.contains(name), statements)))
+	}
+
+	//Statements up to the matching }, separated by ⋄ or newlines. A statement followed by
+	//a colon is a guard.
+	fn parse_dfn_statements(&mut self) -> Result<(Box<Token>, Statements), AplError> {
+		let open = self.stash()?;
+		let mut statements: Vec<Box<Node>> = vec![];
+
+		loop {
+			if self.end_of_source() {
+				return Err(AplError::syntax("unmatched {").at(&open));
+			} else if self.token_is_primitive("}") {
+				return Ok((open, Rc::new(statements)));
+			} else if self.token_is_separator() {
+				self.stash()?;
+				continue;
+			}
+
+			let statement = self.parse_dyadic()?;
+			if self.token_is_primitive(":") {
+				let colon = self.stash()?;
+				let result = self.parse_dyadic()?;
+				statements.push(Box::new(Node::Guard(colon, statement, result)));
+			} else {
+				statements.push(statement);
+			}
+
+			if !(self.token_is_separator() || self.token_is_primitive("}") || self.end_of_source()) {
+				return Err(self.syntax_error("unexpected token in dfn"));
+			}
+		}
+	}
+
+	fn parse_dyadic(&mut self) -> Result<Box<Node>, AplError> {
+		if self.nesting >= MAX_NESTING {
+			return Err(self.error_here(AplError::limit("the expression is nested too deeply")))
+		}
+		self.nesting += 1;
+		let result = self.parse_expression();
+		self.nesting -= 1;
+		result
+	}
+
+	fn parse_expression(&mut self) -> Result<Box<Node>, AplError> {
+		if self.end_of_source() {
+			Err(self.syntax_error("unexpected end of source"))
 		} else {
 			//Parse monadic on the left (otherwise it's an endless loop).
 			match self.parse_monadic() {
 				Ok(left) => {
-					if self.end_of_source() {
+					if self.token_ends_expression() {
 						Ok(left)
+					} else if self.token_is_function_name() {
+						self.parse_function().and_then(|function| self.create_dyadic_result(left, function))
 					} else {
 						//FIXME: We should really avoid copying here
 						let token = self.current_token.clone();
@@ -106,19 +412,21 @@ impl Parser {
 		}
 	}
 
-	fn stash(&mut self) -> Box<Token> {
-		let stash = self.current_token.take().unwrap();
-		let _ = self.read_next_token();
-		stash
+	//Takes the current token and moves on to the next
+	fn stash(&mut self) -> Result<Box<Token>, AplError> {
+		match self.current_token.take() {
+			Some(stash) => {
+				self.read_next_token()?;
+				Ok(stash)
+			},
+			None => Err(AplError::syntax("unexpected end of source"))
+		}
 	}
 
-	pub fn create_monadic_result<F>(&mut self, kind: F) -> Result<Box<Node>, String> where F: FnOnce(Box<Token>, Box<Node>) -> Node, {
-		let stash = self.stash();
+	pub fn create_monadic_result(&mut self, function: Box<Function>) -> Result<Box<Node>, AplError> {
 		match self.parse_dyadic() {
 			Ok(node) => {
-				let item = Box::new(kind(stash, node));
-				let _ = self.read_next_token();
-				Ok(item)
+				Ok(Box::new(Node::Monadic(function, node)))
 			},
 			Err(msg) => {
 				Err(msg)
@@ -126,9 +434,18 @@ impl Parser {
 		}
 	}
 
-	fn parse_monadic(&mut self) -> Result<Box<Node>, String> {
+	fn parse_monadic(&mut self) -> Result<Box<Node>, AplError> {
 		if self.end_of_source() {
-			Err("Unexpected end of source".to_string())
+			Err(self.syntax_error("unexpected end of source"))
+		} else if self.token_is_function_name() {
+			let function = self.parse_function()?;
+			match *function {
+				//A function name can be reassigned to an array
+				Function::Named(token) if self.token_is_primitive("←") => {
+					self.create_assignment(Box::new(Node::Variable(token)))
+				},
+				_ => self.create_monadic_result(function)
+			}
 		} else {
 			//FIXME: We should really avoid copying here
 			let token = self.current_token.clone();
@@ -141,48 +458,89 @@ impl Parser {
 		}
 	}
 
-	pub fn parse_base_expression(&mut self) -> Result<Box<Node>, String> {
+	pub fn parse_base_expression(&mut self) -> Result<Box<Node>, AplError> {
 		//This will either be an Array, a Number, or a Niladic primitive (or a bracketed thingy)
 		if self.end_of_source() {
-			Err("Unexpected end of source".to_string())
+			Err(self.syntax_error("unexpected end of source"))
 		} else {
 			//FIXME: Better error handling
 			//FIXME: We should really avoid copying here
 			let token = self.current_token.clone();
 
 			match token.map(|t| *t) {
-				Some(Token::Number(_)) => self.parse_array(),
-				Some(Token::Variable(_)) => self.parse_variable(),
+				Some(Token::Number(_)) | Some(Token::String(_)) => self.parse_strand(),
+				Some(Token::Variable(_)) => self.parse_strand(),
 				Some(Token::Primitive(ref token_data)) => {
 					match token_data.string.as_str() {
 						"⍬" => self.parse_zilde(),
-						"(" => Err("Not yet implemented".to_string()),
-						_ => Err("Unexpected primitive".to_string())
+						"(" | "⍺" | "⍵" => self.parse_strand(),
+						")" => Err(self.syntax_error("unmatched )")),
+						_ => Err(self.syntax_error("unexpected primitive"))
 					}
 				},
-				_ => Err("Unexpected token".to_string())
+				_ => Err(self.syntax_error("unexpected token"))
 			}
 		}
 	}
 
-	fn parse_array(&mut self) -> Result<Box<Node>, String> {
-		let mut tokens: Vec<Box<Token>> = vec![];
-		while self.token_is_number() {
-			tokens.push(self.current_token.take().unwrap());
-			let _ = self.read_next_token();
+	fn parse_strand(&mut self) -> Result<Box<Node>, AplError> {
+		//A run of literals, names and bracketed subexpressions, e.g. 1 (2 3) X 'abc'
+		let mut items: Vec<Box<Node>> = vec![];
+		let mut literals: Vec<Box<Token>> = vec![];
+		while self.token_is_strand_item() {
+			if self.token_is_literal() {
+				let token = self.stash()?;
+				items.push(Box::new(Node::Array(vec![token.clone()])));
+				literals.push(token);
+			} else if self.token_is_variable() || self.token_is_argument() {
+				items.push(self.parse_variable()?);
+			} else {
+				items.push(self.parse_parenthesized()?);
+			}
+		}
+
+		if literals.len() == items.len() {
+			Ok(Box::new(Node::Array(literals)))
+		} else if items.len() == 1 {
+			Ok(items.pop().unwrap())
+		} else {
+			Ok(Box::new(Node::Strand(items)))
 		}
-		Ok(Box::new(Node::Array(tokens)))
 	}
 
-	fn parse_variable(&mut self) -> Result<Box<Node>, String> {
-		let result = Box::new(Node::Variable(self.current_token.take().unwrap()));
-		let _ = self.read_next_token();
-		Ok(result)
+	fn parse_parenthesized(&mut self) -> Result<Box<Node>, AplError> {
+		let open = self.stash()?;
+
+		if self.token_is_primitive(")") {
+			return Err(AplError::syntax("empty parentheses").at(&open));
+		}
+		if self.token_ends_expression() {
+			return Err(AplError::syntax("unmatched (").at(&open));
+		}
+
+		let inner = self.parse_dyadic()?;
+		if self.token_is_primitive(")") {
+			self.read_next_token()?;
+			Ok(inner)
+		} else {
+			Err(AplError::syntax("unmatched (").at(&open))
+		}
+	}
+
+	fn parse_variable(&mut self) -> Result<Box<Node>, AplError> {
+		Ok(Box::new(Node::Variable(self.stash()?)))
 	}
 
-	fn parse_zilde(&mut self) -> Result<Box<Node>, String> {
-		let result = Box::new(Node::Zilde(self.current_token.take().unwrap()));
-		let _ = self.read_next_token();
-		Ok(result)
+	fn parse_zilde(&mut self) -> Result<Box<Node>, AplError> {
+		Ok(Box::new(Node::Zilde(self.stash()?)))
+	}
+}
+
+//Names, or ⍺ for a default left argument
+fn is_assignable(target: &Node) -> bool {
+	match target {
+		Node::Variable(_) => true,
+		Node::Strand(items) => items.iter().all(|item| matches!(item.as_ref(), Node::Variable(_))),
+		_ => false
 	}
 }
@@ -1,132 +1,149 @@
-use std::result;
-use parser::Parser;
-use nodes;
-
-
-fn test_parse(input: ~str, f: |tree: ~nodes::Node|) {
+use crate::{
+	error::AplError,
+	nodes::{
+		Function,
+		Node,
+	},
+	parser::Parser,
+	tokenizer::Token,
+};
+
+fn text(token: &Token) -> String {
+	match token {
+		Token::String(token_data) => format!("'{}'", token_data.string),
+		_ => token.token_data().map(|token_data| token_data.string.clone()).unwrap_or_default()
+	}
+}
 
-	let mut parser = Parser::new(input);
-	match parser.parse_next_statement() {
-		result::Ok(tree) => {
-			f(tree)
-		},
-		result::Err(msg) => {
-			fail!(msg)
+//A tree written out with every call in parentheses, its function first
+fn function_tree(function: &Function) -> String {
+	match function {
+		Function::Primitive(token) | Function::Named(token) => text(token),
+		Function::Derived(operator, operand) if text(operator) == "∘." => format!("∘.{}", function_tree(operand)),
+		Function::Derived(operator, operand) => format!("{}{}", function_tree(operand), text(operator)),
+		Function::DerivedDyadic(operator, left, right) => format!("{}{}{}", function_tree(left), text(operator), function_tree(right)),
+		Function::Axis(_, function, axis) => format!("{}[{}]", function_tree(function), tree(axis)),
+		Function::Dfn(_, body) => {
+			let statements: Vec<String> = body.parsed.borrow().1.iter().map(|statement| tree(statement)).collect();
+			format!("{{{}}}", statements.join(" ⋄ "))
 		}
 	}
 }
 
-#[test]
-fn test_parse_number() {
+fn tree(node: &Node) -> String {
+	match node {
+		Node::Assignment(_, target, value) => format!("(← {} {})", tree(target), tree(value)),
+		Node::FunctionAssignment(_, target, function) => format!("(← {} {})", tree(target), function_tree(function)),
+		Node::Guard(_, condition, result) => format!("(: {} {})", tree(condition), tree(result)),
+		Node::Monadic(function, right) => format!("({} {})", function_tree(function), tree(right)),
+		Node::Dyadic(function, left, right) => format!("({} {} {})", function_tree(function), tree(left), tree(right)),
+		Node::Variable(token) | Node::Zilde(token) => text(token),
+		Node::Array(tokens) => tokens.iter().map(|token| text(token)).collect::<Vec<String>>().join(" "),
+		Node::Strand(items) => format!("[{}]", items.iter().map(|item| match &**item {
+			Node::Array(tokens) if tokens.len() > 1 => format!("({})", tree(item)),
+			_ => tree(item)
+		}).collect::<Vec<String>>().join(" "))
+	}
+}
 
-	test_parse(~"3.141", |tree| {
-		match tree {
-			~nodes::Array(_) => {
-				//OK
-			},
-			_ => {
-				fail!(~"Didn't find a number");
-			}
-		}
-	});
+//Parses every statement in the source, taking the given names to be functions
+fn parse_all(source: &str, functions: &[&str]) -> Result<Vec<String>, AplError> {
+	let mut parser = Parser::new(source.to_string());
+	for name in functions {
+		parser.declare_function(name);
+	}
+	let mut statements = vec![];
+	while let Some(node) = parser.parse_next_statement()? {
+		statements.push(tree(&node));
+	}
+	Ok(statements)
+}
 
-	test_parse(~"0J3.141", |tree| {
-		match tree {
-			~nodes::Array(_) => {
-				//OK
-			},
-			_ => {
-				fail!(~"Didn't find a number");
-			}
-		}
-	});
+fn assert_parses(source: &str, expected: &[&str]) {
+	assert_eq!(parse_all(source, &["f"]), Ok(expected.iter().map(|statement| statement.to_string()).collect()));
 }
 
 #[test]
-fn test_parse_array() {
-
-	test_parse(~"1 2 3 4", |tree| {
-		match tree {
-			~nodes::Array(_) => {
-				//OK
-			},
-			_ => {
-				fail!(~"Didn't find an array");
-			}
-		}
-	});
+fn test_parse_arrays() {
+	assert_parses("3.141", &["3.141"]);
+	assert_parses("1 ¯2 0J3.5", &["1 ¯2 0J3.5"]);
+	assert_parses("'ab'", &["'ab'"]);
+	assert_parses("'it''s'", &["'it's'"]);
+	assert_parses("''", &["''"]);
+	assert_parses("1 'ab' 2", &["1 'ab' 2"]);
+	assert_parses("⍬", &["⍬"]);
 }
 
 #[test]
-fn test_parse_variable() {
+fn test_parse_strands() {
+	assert_parses("x y", &["[x y]"]);
+	assert_parses("1 x", &["[1 x]"]);
+	assert_parses("(1 2)(3 4)", &["[(1 2) (3 4)]"]);
+	assert_parses("(1 2) 3", &["[(1 2) 3]"]);
+}
 
-	test_parse(~"Trololo", |tree| {
-		match tree {
-			~nodes::Variable(_) => {
-				//OK
-			},
-			_ => {
-				fail!(~"Didn't find a variable");
-			}
-		}
-	});
+#[test]
+fn test_parse_right_to_left() {
+	assert_parses("-1", &["(- 1)"]);
+	assert_parses("1 2+3 4", &["(+ 1 2 3 4)"]);
+	assert_parses("2×3+4", &["(× 2 (+ 3 4))"]);
+	assert_parses("(2×3)+4", &["(+ (× 2 3) 4)"]);
+	assert_parses("-2×3", &["(- (× 2 3))"]);
+	assert_parses("((1))", &["1"]);
 }
 
 #[test]
-fn test_parse_zilde() {
+fn test_parse_operators() {
+	assert_parses("+/⍳5", &["(+/ (⍳ 5))"]);
+	assert_parses("+¨1 2", &["(+¨ 1 2)"]);
+	assert_parses("1 2∘.×3 4", &["(∘.× 1 2 3 4)"]);
+	assert_parses("1 2+.×3 4", &["(+.× 1 2 3 4)"]);
+}
 
-	test_parse(~"⍬", |tree| {
-		match tree {
-			~nodes::Zilde(_) => {
-				//OK
-			},
-			_ => {
-				fail!(~"Didn't find zilde");
-			}
-		}
-	});
+#[test]
+fn test_parse_axis() {
+	assert_parses(",[1]x", &["(,[1] x)"]);
+	assert_parses("⌽[1]2 3⍴⍳6", &["(⌽[1] (⍴ 2 3 (⍳ 6)))"]);
+	assert_parses("+/[1]x", &["(+/[1] x)"]);
 }
 
 #[test]
-fn test_conjugate() {
+fn test_parse_assignment() {
+	assert_parses("x←1 2", &["(← x 1 2)"]);
+	assert_parses("x←y←2", &["(← x (← y 2))"]);
+	assert_parses("1+x←2", &["(+ 1 (← x 2))"]);
+	assert_parses("g←{⍵+1}", &["(← g {(+ ⍵ 1)})"]);
+}
 
-	test_parse(~"+1", |tree| {
-		match tree {
-			~nodes::Conjugate(_, ~nodes::Array(_)) => {
-				//OK
-			},
-			_ => {
-				fail!(~"Didn't find conjugate one");
-			}
-		}
-	});
+#[test]
+fn test_parse_functions() {
+	assert_parses("f 1", &["(f 1)"]);
+	assert_parses("1 f 2", &["(f 1 2)"]);
+	assert_parses("f f x", &["(f (f x))"]);
+	assert_parses("g 1", &["[g 1]"]);
+	assert_parses("g←{⍵} ⋄ g 1", &["(← g {⍵})", "(g 1)"]);
+}
 
+#[test]
+fn test_parse_dfns() {
+	assert_parses("{⍺+⍵}/1 2", &["({(+ ⍺ ⍵)}/ 1 2)"]);
+	assert_parses("{⍵>0:⍵ ⋄ -⍵} 3", &["({(: (> ⍵ 0) ⍵) ⋄ (- ⍵)} 3)"]);
+	assert_parses("{⍵=0:1 ⋄ ⍵×∇⍵-1} 5", &["({(: (= ⍵ 0) 1) ⋄ (× ⍵ (∇ (- ⍵ 1)))} 5)"]);
+	assert_parses("{x←⍵\nx+1} 2", &["({(← x ⍵) ⋄ (+ x 1)} 2)"]);
 }
 
 #[test]
-fn test_negate() {
-	test_parse(~"-1", |tree| {
-		match tree {
-			~nodes::Negate(_, ~nodes::Array(_)) => {
-				//OK
-			},
-			_ => {
-				fail!(~"Didn't find conjugate one");
-			}
-		}
-	});
+fn test_parse_statements() {
+	assert_parses("1⋄2\n3", &["1", "2", "3"]);
+	assert_parses("", &[]);
+	assert_parses("x←1\n\nx", &["(← x 1)", "x"]);
 }
 
 #[test]
-fn test_addition() {
-	test_parse(~"1 2 3 4 + 2 4 6 8", |tree| {
-		match tree {
-			~nodes::Addition(_, ~nodes::Array(_), ~nodes::Array(_)) => {
-				//OK
-			},
-			_ => {
-				fail!(~"Didn't find the right Dyadic expression");
-			}
-		}
-	});
+fn test_parse_errors() {
+	for source in ["(1 2", "1 2)", "{⍵+1", "1+", "+", "x←", "⍵}"] {
+		assert!(matches!(parse_all(source, &[]), Err(AplError::Syntax(..))), "{} should not parse", source);
+	}
+	let nested = format!("{}1{}", "(".repeat(200), ")".repeat(200));
+	assert_eq!(parse_all(&nested, &[]), Err(AplError::limit("the expression is nested too deeply").at_position(1, 129)));
 }
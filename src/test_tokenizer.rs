@@ -1,100 +1,122 @@
-use std::result;
-use tokenizer;
-use tokenizer::Tokenizer;
-use test_utils::test_assert;
+use crate::{
+	tokenizer::{
+		Token,
+		Tokenizer,
+	},
+	test_utils::test_assert,
+};
+
+//Every token in the source up to the end, each with its text and position
+fn read_all(source: &str) -> Vec<(String, usize, usize)> {
+	let mut tokenizer = Tokenizer::new(source.to_string());
+	let mut tokens = vec![];
+	loop {
+		match tokenizer.read_next_token() {
+			Ok(token) => {
+				match token.token_data() {
+					Some(token_data) => {
+						tokens.push((token_data.string.clone(), token_data.row, token_data.col));
+					},
+					None => {
+						break;
+					}
+				}
+			},
+			Err(error) => {
+				panic!("{:?} - {}", source, error);
+			}
+		}
+	}
+	tokens
+}
+
+fn test_positions(source: &str, expected: &[(&str, usize, usize)]) {
+	let tokens = read_all(source);
+	let expected: Vec<(String, usize, usize)> = expected.iter()
+		.map(|&(string, row, col)| (string.to_string(), row, col))
+		.collect();
+	assert_eq!(tokens, expected, "{:?}", source);
+}
 
 #[test]
 fn test_tokenize_number() {
-	let list = ~[~"1", ~"321", ~"3.21", ~".21", ~"0.21", ~"¯321"];
+	let list = ["1", "321", "3.21", ".21", "0.21", "¯321"];
 	for number in list.iter() {
-		let mut tokenizer = Tokenizer::new(number.clone());
-		match tokenizer.read_next_token() {
-			result::Ok(~tokenizer::Number(tokenData)) => {
-
+		let mut tokenizer = Tokenizer::new(number.to_string());
+		match tokenizer.read_next_token().as_deref() {
+			Ok(Token::Number(token_data)) => {
 				//Pass
-				test_assert(tokenData.string == *number, format!("Read {} expected {} ", tokenData.string, *number));
-				/*fail_unless!(tokenData.row == 0);
-				fail_unless!(tokenData.col == 0);*/
+				test_assert(token_data.string == *number, format!("Read {} expected {} ", token_data.string, number));
+				test_assert(token_data.row == 1 && token_data.col == 1, format!("Read {} at {}:{}", number, token_data.row, token_data.col));
 			},
-			result::Err(msg) => {
-				fail!(format!("Expected {} - {}", *number, msg));
+			Err(error) => {
+				panic!("Expected {} - {}", number, error);
 			},
 			_ => {
-				fail!(format!("Unexpected token type for {}", *number));
+				panic!("Unexpected token type for {}", number);
 			}
 		}
 	}
 
 	//Complex number
-	let list = ~[~"1J2", ~"0J21", ~"3.2J2.1", ~"¯321J¯321"];
+	let list = ["1J2", "0J21", "3.2J2.1", "¯321J¯321"];
 	for number in list.iter() {
-		let mut tokenizer = Tokenizer::new(number.clone());
-		match tokenizer.read_next_token() {
-			result::Ok(~tokenizer::Number(tokenData)) => {
-
+		let mut tokenizer = Tokenizer::new(number.to_string());
+		match tokenizer.read_next_token().as_deref() {
+			Ok(Token::Number(token_data)) => {
 				//Pass
-				test_assert(tokenData.string == *number, format!("Read {} expected {} ", tokenData.string, *number));
-				/*fail_unless!(tokenData.row == 0);
-				fail_unless!(tokenData.col == 0);*/
+				test_assert(token_data.string == *number, format!("Read {} expected {} ", token_data.string, number));
 			},
-			result::Err(msg) => {
-				fail!(format!("Expected {} - {}", *number, msg));
+			Err(error) => {
+				panic!("Expected {} - {}", number, error);
 			},
 			_ => {
-				fail!(format!("Unexpected token type for {}", *number));
+				panic!("Unexpected token type for {}", number);
 			}
 		}
 	}
+
 	//Offset number
-	let mut tokenizer = Tokenizer::new(~" 123⍝ lol");
-	let expected = ~"123";
-	match tokenizer.read_next_token() {
-		result::Ok(~tokenizer::Number(tokenData)) => {
+	let mut tokenizer = Tokenizer::new(" 123⍝ lol".to_string());
+	let expected = "123";
+	match tokenizer.read_next_token().as_deref() {
+		Ok(Token::Number(token_data)) => {
 			//Pass
-			test_assert(tokenData.string == expected, format!("Read {} expected {} ", tokenData.string, expected));
-			/*fail_unless!(tokenData.row == 0);
-			fail_unless!(tokenData.col == 1);*/
+			test_assert(token_data.string == expected, format!("Read {} expected {} ", token_data.string, expected));
+			test_assert(token_data.row == 1 && token_data.col == 2, format!("Read {} at {}:{}", expected, token_data.row, token_data.col));
 		},
-		result::Err(msg) => {
-			fail!(format!("Expected {} - {}", expected, msg));
+		Err(error) => {
+			panic!("Expected {} - {}", expected, error);
 		},
 		_ => {
-			fail!(format!("Unexpected token type for {}", expected));
+			panic!("Unexpected token type for {}", expected);
 		}
 	}
 
 	//Invalid numbers
-	let list = [~".3.21", ~"3.2.1", ~"1.", ~".", ~"JJ", ~"1J", ~"J1", ~"0J1¯"];
+	let list = [".3.21", "3.2.1", "1.", ".", "JJ", "1J", "J1", "0J1¯"];
 	for number in list.iter() {
-		let mut tokenizer = Tokenizer::new(number.clone());
-		match tokenizer.read_next_token() {
-			result::Ok(~tokenizer::Number(tokenData)) => {
-				fail!(format!("Unexpectedly read {} from source {}",tokenData.string, *number));
-			},
-			result::Ok(_) => {
-			},
-			result::Err(_msg) => {
-			}
+		let mut tokenizer = Tokenizer::new(number.to_string());
+		if let Ok(Token::Number(token_data)) = tokenizer.read_next_token().as_deref() {
+			panic!("Unexpectedly read {} from source {}", token_data.string, number);
 		}
 	}
 }
 
 #[test]
 fn test_tokenize_newlines() {
-	let list = [~"\n", ~"  \n", ~"\n\n", ~"⍝ lol\n", ~"\r", ~"\r\n", ~"\r\r"];
+	let list = ["\n", "  \n", "\n\n", "⍝ lol\n", "\r", "\r\n", "\r\r"];
 	for newline in list.iter() {
-		let mut tokenizer = Tokenizer::new(newline.clone());
-		match tokenizer.read_next_token() {
-			result::Ok(~tokenizer::Newline(_tokenData)) => {
+		let mut tokenizer = Tokenizer::new(newline.to_string());
+		match tokenizer.read_next_token().as_deref() {
+			Ok(Token::Newline(_token_data)) => {
 				//Pass
-				/*fail_unless!(tokenData.row == 0);
-				fail_unless!(tokenData.col == 0);*/
 			},
-			result::Err(msg) => {
-				fail!(format!("Expected newline - {}", msg));
+			Err(error) => {
+				panic!("Expected newline - {}", error);
 			},
 			_ => {
-				fail!(~"Unexpected token type");
+				panic!("Unexpected token type");
 			}
 		}
 	}
@@ -103,31 +125,28 @@ fn test_tokenize_newlines() {
 #[test]
 fn test_tokenize_strings() {
 	//Standard strings
-	let list = [(~"'Hello'", ~"Hello"),
-		(~"\"Double quotes\"", ~"Double quotes"),
-		(~"'Anything ⍝ lol'", ~"Anything ⍝ lol"),
-		(~"'Inner \"\" quotes'", ~"Inner \"\" quotes"),
-		(~"\"Inner '' quotes\"", ~"Inner '' quotes"),
-		(~"'Escaped '' quote'", ~"Escaped ' quote"),
-		(~"\"Escaped \"\" quote\"", ~"Escaped \" quote"),
-		(~"\"Not Escaped '' quote\"", ~"Not Escaped '' quote"),
-		(~"'Not Escaped \"\" quote'", ~"Not Escaped \"\" quote")
+	let list = [("'Hello'", "Hello"),
+		("\"Double quotes\"", "Double quotes"),
+		("'Anything ⍝ lol'", "Anything ⍝ lol"),
+		("'Inner \"\" quotes'", "Inner \"\" quotes"),
+		("\"Inner '' quotes\"", "Inner '' quotes"),
+		("'Escaped '' quote'", "Escaped ' quote"),
+		("\"Escaped \"\" quote\"", "Escaped \" quote"),
+		("\"Not Escaped '' quote\"", "Not Escaped '' quote"),
+		("'Not Escaped \"\" quote'", "Not Escaped \"\" quote")
 	];
-	for &(ref string, ref result) in list.iter() {
-		let mut tokenizer = Tokenizer::new(string.clone());
-		match tokenizer.read_next_token() {
-			result::Ok(~tokenizer::String(tokenData)) => {
-				test_assert(tokenData.string == *result, format!("Read {} expected {} ", tokenData.string, *result));
-
+	for &(string, result) in list.iter() {
+		let mut tokenizer = Tokenizer::new(string.to_string());
+		match tokenizer.read_next_token().as_deref() {
+			Ok(Token::String(token_data)) => {
 				//Pass
-				/*fail_unless!(tokenData.row == 0);
-				fail_unless!(tokenData.col == 0);*/
+				test_assert(token_data.string == result, format!("Read {} expected {} ", token_data.string, result));
 			},
-			result::Err(msg) => {
-				fail!(format!("Expected string for {} - {}", tokenizer.char_reader.source, msg));
+			Err(error) => {
+				panic!("Expected string for {} - {}", string, error);
 			},
 			_ => {
-				fail!(~"Unexpected token type");
+				panic!("Unexpected token type");
 			}
 		}
 	}
@@ -135,96 +154,24 @@ fn test_tokenize_strings() {
 
 #[test]
 fn test_tokenize_primitives() {
-	let list = [~"+",
-		~"−",
-		~"×",
-		~"÷",
-		~"⌈",
-		~"⌊",
-		~"∣",
-		~"⍳",
-		~"?",
-		~"⋆",
-		~"⍟",
-		~"○",
-		~"!",
-		~"⌹",
-		~"<",
-		~"≤",
-		~"=",
-		~"≥",
-		~">",
-		~"≠",
-		~"≡",
-		~"≢",
-		~"∊",
-		~"⍷",
-		~"∪",
-		~"∩",
-		~"~",
-		~"∨",
-		~"∧",
-		~"⍱",
-		~"⍲",
-		~"⍴",
-		~",",
-		~"⍪",
-		~"⌽",
-		~"⊖",
-		~"⍉",
-		~"↑",
-		~"↓",
-		~"⊂",
-		~"⊃",
-		~"⌷",
-		~"⍋",
-		~"⍒",
-		~"⊤",
-		~"⊥",
-		~"⍺",
-		~"⍕",
-		~"⍎",
-		~"⊣",
-		~"⊢",
-		~"▯",
-		~"⍞",
-		~"/",
-		~"⌿",
-		~"\\",
-		~"⍀",
-		~"⌿",
-		~"∘.",
-		~"∘",
-		~"¨",
-		~"[",
-		~"]",
-		~"⍬",
-		~"⋄",
-		~"∇",
-		~"⍫",
-		~"(",
-		~")",
-		~"←",
-		~"{",
-		~"}",
-		~"⍵",
-		~"."
-	];
+	let list = ["+", "−", "×", "÷", "⌈", "⌊", "∣", "⍳", "?", "⋆", "⍟", "○", "!", "⌹", "<", "≤", "=",
+		"≥", ">", "≠", "≡", "≢", "∊", "⍷", "∪", "∩", "~", "∨", "∧", "⍱", "⍲", "⍴", ",", "⍪", "⌽",
+		"⊖", "⍉", "↑", "↓", "⊂", "⊃", "⌷", "⍋", "⍒", "⊤", "⊥", "⍺", "⍕", "⍎", "⊣", "⊢", "▯", "⍞",
+		"/", "⌿", "\\", "⍀", "⌿", "∘.", "∘", "¨", "[", "]", "⍬", "⋄", "∇", "⍫", "(", ")", "←",
+		"{", "}", "⍵", "."];
 
 	for prim in list.iter() {
-		let mut tokenizer = Tokenizer::new(prim.clone());
+		let mut tokenizer = Tokenizer::new(prim.to_string());
 
-		match tokenizer.read_next_token() {
-			result::Ok(~tokenizer::Primitive(tokenData)) => {
-				test_assert(tokenData.string == *prim, format!("Read {} expected {} ", tokenData.string, *prim));
-				/*fail_unless!(tokenData.row == 0);
-				fail_unless!(tokenData.col == 0);*/
+		match tokenizer.read_next_token().as_deref() {
+			Ok(Token::Primitive(token_data)) => {
+				test_assert(token_data.string == *prim, format!("Read {} expected {} ", token_data.string, prim));
 			},
-			result::Err(msg) => {
-				fail!(format!("Expected primitive for {} - {}", *prim, msg));
+			Err(error) => {
+				panic!("Expected primitive for {} - {}", prim, error);
 			},
 			_ => {
-				fail!(format!("Unexpected token type for {}", *prim));
+				panic!("Unexpected token type for {}", prim);
 			}
 		}
 	}
@@ -233,28 +180,25 @@ fn test_tokenize_primitives() {
 #[test]
 fn test_tokenize_variables() {
 	//Standard Variables
-	let list = [(~"Hello", ~"Hello"),
-		(~"hi", ~"hi"),
-		(~"HOLA⍝comment", ~"HOLA"),
-		(~"∆delta", ~"∆delta"),
-		(~"⍙delta", ~"⍙delta")
+	let list = [("Hello", "Hello"),
+		("hi", "hi"),
+		("HOLA⍝comment", "HOLA"),
+		("∆delta", "∆delta"),
+		("⍙delta", "⍙delta")
 	];
 
-	for &(ref string, ref result) in list.iter() {
-		let mut tokenizer = Tokenizer::new(string.clone());
-		match tokenizer.read_next_token() {
-			result::Ok(~tokenizer::Variable(tokenData)) => {
-				test_assert(tokenData.string == *result, format!("Read {} expected {} ", tokenData.string, *result));
-
+	for &(string, result) in list.iter() {
+		let mut tokenizer = Tokenizer::new(string.to_string());
+		match tokenizer.read_next_token().as_deref() {
+			Ok(Token::Variable(token_data)) => {
 				//Pass
-				/*fail_unless!(tokenData.row == 0);
-				fail_unless!(tokenData.col == 0);*/
+				test_assert(token_data.string == result, format!("Read {} expected {} ", token_data.string, result));
 			},
-			result::Err(msg) => {
-				fail!(format!("Expected variable for {} - {}", tokenizer.char_reader.source, msg));
+			Err(error) => {
+				panic!("Expected variable for {} - {}", string, error);
 			},
 			_ => {
-				fail!(~"Unexpected token type");
+				panic!("Unexpected token type");
 			}
 		}
 	}
@@ -262,34 +206,69 @@ fn test_tokenize_variables() {
 
 #[test]
 fn test_tokenize_multiple() {
-	let string = ~"life←{↑1 ⍵∨.∧3 4=+/,¯1 0 1∘.⊖¯1 0 1∘.⌽⊂⍵}";
-	let mut tokenizer = Tokenizer::new(string);
-	let mut tokens = ~[];
-	loop {
-		match tokenizer.read_next_token() {
-			result::Ok(token) => {
-				match token {
-					~tokenizer::EndOfFile => {
-						break;
-					},
-					_ => {
-						tokens.push(token);
-					}
-				}
-			},
-			result::Err(msg) => {
-				fail!(msg);
-			}
-		}
-	}
+	let tokens = read_all("life←{↑1 ⍵∨.∧3 4=+/,¯1 0 1∘.⊖¯1 0 1∘.⌽⊂⍵}");
 	test_assert(tokens.len() == 28, format!("Expected 28 tokens, got {}", tokens.len()));
 }
-/*
-TODO:
-//Control
-let string = ~":If";
-let string = ~":Return";
-//Label
-let string = ~"LABEL:";
-//System variables
-let string = ~"⎕SI";*/
+
+#[test]
+fn test_tokenize_positions_newlines() {
+	//\r\n is a single line break, as is a lone \r
+	test_positions("1\r\n22\r\n3", &[
+		("1", 1, 1), ("\r\n", 1, 2), ("22", 2, 1), ("\r\n", 2, 3), ("3", 3, 1)
+	]);
+	test_positions("1\r2\r\r3", &[
+		("1", 1, 1), ("\r", 1, 2), ("2", 2, 1), ("\r", 2, 2), ("\r", 3, 1), ("3", 4, 1)
+	]);
+	test_positions("1\n\r\n2", &[
+		("1", 1, 1), ("\n", 1, 2), ("\r\n", 2, 1), ("2", 3, 1)
+	]);
+	test_positions("x⍝ comment\r\ny", &[
+		("x", 1, 1), ("\r\n", 1, 11), ("y", 2, 1)
+	]);
+}
+
+#[test]
+fn test_tokenize_positions_tabs() {
+	//A tab is one column, like any other character
+	test_positions("\t1\t+ 2", &[
+		("1", 1, 2), ("+", 1, 4), ("2", 1, 6)
+	]);
+	test_positions("1\n\t\tx", &[
+		("1", 1, 1), ("\n", 1, 2), ("x", 2, 3)
+	]);
+}
+
+#[test]
+fn test_tokenize_positions_glyphs() {
+	//Columns count characters, not the bytes of their encoding
+	test_positions("⍴⍳3", &[
+		("⍴", 1, 1), ("⍳", 1, 2), ("3", 1, 3)
+	]);
+	test_positions("'é⍝'≢¯1", &[
+		("é⍝", 1, 1), ("≢", 1, 5), ("¯1", 1, 6)
+	]);
+	test_positions("∆x←⎕IO", &[
+		("∆x", 1, 1), ("←", 1, 3), ("⎕IO", 1, 4)
+	]);
+}
+
+#[test]
+fn test_tokenize_positions_backtracking() {
+	//A dot before a digit starts a number, and is otherwise the inner product's dot
+	test_positions(".5+.×.25", &[
+		(".5", 1, 1), ("+", 1, 3), (".", 1, 4), ("×", 1, 5), (".25", 1, 6)
+	]);
+	test_positions("1 .2", &[
+		("1", 1, 1), (".2", 1, 3)
+	]);
+	//∘ only joins a following dot to make the outer product
+	test_positions("1∘.+2", &[
+		("1", 1, 1), ("∘.", 1, 2), ("+", 1, 4), ("2", 1, 5)
+	]);
+	test_positions("f∘g ∘", &[
+		("f", 1, 1), ("∘", 1, 2), ("g", 1, 3), ("∘", 1, 5)
+	]);
+	test_positions("∘.\n∘.5", &[
+		("∘.", 1, 1), ("\n", 1, 3), ("∘.", 2, 1), ("5", 2, 3)
+	]);
+}
@@ -1,10 +1,10 @@
+use crate::error::AplError;
+
 #[derive(Clone, Debug)]
 pub struct TokenData {
 	pub string: String,
-	#[allow(dead_code)]
-	row: usize,
-	#[allow(dead_code)]
-	col: usize
+	pub row: usize,
+	pub col: usize
 }
 
 #[derive(Clone, Debug)]
@@ -17,6 +17,16 @@ pub enum Token {
 	EndOfFile
 }
 
+impl Token {
+	pub fn token_data(&self) -> Option<&TokenData> {
+		match self {
+			Token::Number(token_data) | Token::Newline(token_data) | Token::String(token_data) |
+			Token::Primitive(token_data) | Token::Variable(token_data) => Some(token_data),
+			Token::EndOfFile => None
+		}
+	}
+}
+
 struct Backtrack {
 	initial_next: usize,
 	initial_char: Option<char>,
@@ -38,17 +48,32 @@ impl CharReader {
 			source: input_string,
 			next: 0,
 			current_char: None,
-			row: 0,
+			row: 1,
 			col: 0
 		}
 	}
 
+	//row and col are the 1-based position of current_char, counting columns in chars.
+	//A \n, a \r\n pair or a lone \r ends a row.
 	fn read_and_stash_char(&mut self) {
+		match self.current_char {
+			Some('\n') => {
+				self.row += 1;
+				self.col = 1;
+			},
+			Some('\r') if !self.source[self.next..].starts_with('\n') => {
+				self.row += 1;
+				self.col = 1;
+			},
+			_ => {
+				self.col += 1;
+			}
+		}
+
 		if self.next < self.source.len() {
 			let ch = self.source[self.next..].chars().next().unwrap();
 			let next = self.next + ch.len_utf8();
 			self.next = next;
-			self.row += 1;
 			self.current_char = Some(ch);
 		} else {
 			self.current_char = None;
@@ -56,7 +81,7 @@ impl CharReader {
 	}
 
 	fn wind_past_whitespace(&mut self) {
-		while let Some(' ') = self.current_char {
+		while let Some(' ' | '\t') = self.current_char {
 			self.read_and_stash_char();
 		}
 	}
@@ -72,6 +97,10 @@ impl CharReader {
 		}
 	}
 
+	fn position(&self) -> (usize, usize) {
+		(self.row, self.col)
+	}
+
 	fn create_backtrack(&self) -> Backtrack {
 		Backtrack {
 			initial_next: self.next,
@@ -102,7 +131,7 @@ impl Tokenizer {
 		}
 	}
 
-	pub fn read_next_token(&mut self) -> Result<Box<Token>, String> {
+	pub fn read_next_token(&mut self) -> Result<Box<Token>, AplError> {
 		self.char_reader.wind_past_whitespace();
 		self.char_reader.wind_past_comments();
 		match self.char_reader.current_char {
@@ -126,7 +155,8 @@ impl Tokenizer {
 				if is_valid_variable_start(first_char) {
 					return variable_tokenizer(&mut self.char_reader)
 				}
-				Err(format!("No valid token found starting with {}", first_char))
+				let (row, col) = self.char_reader.position();
+				Err(AplError::syntax(format!("no valid token starts with {}", first_char)).at_position(row, col))
 			},
 			None => {
 				Ok(Box::new(Token::EndOfFile))
@@ -161,7 +191,8 @@ fn is_negative(char_reader: &CharReader) -> bool {
 	matches!(char_reader.current_char, Some('¯'))
 }
 
-fn number_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, String> {
+fn number_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, AplError> {
+	let (row, col) = char_reader.position();
 	let mut period_encountered = false;
 	let mut complex_encountered = false;
 	let mut first_character = true;
@@ -180,11 +211,11 @@ fn number_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, String>
 				allowed_negative = false;
 				token.push(char_reader.current_char.unwrap());
 			} else {
-				return Err("Invalid number".to_string());
+				return Err(AplError::syntax("invalid number").at_position(row, col));
 			}
 		} else if is_complex(char_reader) {
 			if complex_encountered {
-				return Err("Invalid number".to_string());
+				return Err(AplError::syntax("invalid number").at_position(row, col));
 			} else {
 				complex_encountered = true;
 				period_encountered = false;
@@ -194,7 +225,7 @@ fn number_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, String>
 		} else if is_period(char_reader) {
 			allowed_negative = false;
 			if period_encountered {
-				return Err("Invalid number".to_string());
+				return Err(AplError::syntax("invalid number").at_position(row, col));
 			} else {
 				period_encountered = true;
 				token.push(char_reader.current_char.unwrap());
@@ -206,12 +237,12 @@ fn number_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, String>
 			if token[token.len() - 1] == '.' ||
 				token[token.len() - 1] == 'J' ||
 				token[token.len() - 1] == '¯' {
-				return Err("Invalid number".to_string());
+				return Err(AplError::syntax("invalid number").at_position(row, col));
 			}
 			return Ok(Box::new(Token::Number(TokenData {
 				string: token.into_iter().collect(),
-				row: 0,
-				col: 0
+				row,
+				col
 			})));
 		}
 		char_reader.read_and_stash_char();
@@ -222,7 +253,8 @@ fn is_valid_newline_start(char: char) -> bool {
 	char == '\n' || char == '\r'
 }
 
-fn newline_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, String> {
+fn newline_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, AplError> {
+	let (row, col) = char_reader.position();
 	match char_reader.current_char {
 		Some('\r') => {
 			char_reader.read_and_stash_char();
@@ -231,15 +263,15 @@ fn newline_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, String>
 					char_reader.read_and_stash_char();
 					Ok(Box::new(Token::Newline(TokenData {
 						string: "\r\n".to_string(),
-						row: 0,
-						col: 0
+						row,
+						col
 					})))
 				},
 				_ => {
 					Ok(Box::new(Token::Newline(TokenData {
 						string: "\r".to_string(),
-						row: 0,
-						col: 0
+						row,
+						col
 					})))
 				}
 			}
@@ -248,8 +280,8 @@ fn newline_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, String>
 			char_reader.read_and_stash_char();
 			Ok(Box::new(Token::Newline(TokenData {
 				string: "\n".to_string(),
-				row: 0,
-				col: 0
+				row,
+				col
 			})))
 		}
 	}
@@ -259,7 +291,8 @@ fn is_valid_string_start(char: char) -> bool {
 	char == '\'' || char == '"'
 }
 
-fn string_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, String> {
+fn string_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, AplError> {
+	let (row, col) = char_reader.position();
 	let mut token: Vec<char> = vec![];
 	let opening_character = char_reader.current_char.unwrap();
 	char_reader.read_and_stash_char();
@@ -267,20 +300,17 @@ fn string_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, String>
 	loop {
 		match char_reader.current_char {
 			Some(char) if opening_character == char => {
-				//Lookahead
-				let backtrack = char_reader.create_backtrack();
+				//A doubled quote stands for one quote, otherwise the string is closed
 				char_reader.read_and_stash_char();
 				match char_reader.current_char {
 					Some(char) if opening_character == char => {
-						//It's a quote - continue
 						token.push(char);
 					},
 					_ => {
-						char_reader.backtrack(&backtrack);
 						return Ok(Box::new(Token::String(TokenData {
 							string: token.into_iter().collect(),
-							row: 0,
-							col: 0
+							row,
+							col
 						})));
 					}
 				}
@@ -289,7 +319,7 @@ fn string_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, String>
 				token.push(char);
 			},
 			None => {
-				return Err("Unexpected end of file".to_string());
+				return Err(AplError::syntax("unterminated string").at_position(row, col));
 			}
 		};
 		char_reader.read_and_stash_char();
@@ -297,10 +327,12 @@ fn string_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, String>
 }
 
 fn is_valid_variable_start(char: char) -> bool {
-	char == '∆' || char == '⍙' || ('A'..='z').contains(&char)
+	//⎕ starts the names of system variables such as ⎕IO
+	char == '∆' || char == '⍙' || char == '⎕' || ('A'..='z').contains(&char)
 }
 
-fn variable_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, String> {
+fn variable_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, AplError> {
+	let (row, col) = char_reader.position();
 	let mut token: Vec<char> = vec![];
 
 	while let Some(ch) = char_reader.current_char {
@@ -313,8 +345,8 @@ fn variable_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, String
 
 	Ok(Box::new(Token::Variable(TokenData {
 		string: token.into_iter().collect(),
-		row: 0,
-		col: 0
+		row,
+		col
 	})))
 }
 
@@ -322,7 +354,8 @@ fn is_dot(char: char) -> bool {
 	char == '.'
 }
 
-fn dot_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, String> {
+fn dot_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, AplError> {
+	let (row, col) = char_reader.position();
 	let backtrack = char_reader.create_backtrack();
 	char_reader.read_and_stash_char();
 	match char_reader.current_char {
@@ -333,37 +366,37 @@ fn dot_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, String> {
 		_ => {
 			Ok(Box::new(Token::Primitive(TokenData {
 				string: ".".to_string(),
-				row: 0,
-				col: 0
+				row,
+				col
 			})))
 		}
 	}
 }
 
 fn is_valid_primitive_start(char: char) -> bool {
-	vec!['+','−','×','÷','⌈','⌊','∣','|','⍳','?','⋆','*','⍟','○','!','⌹','<','≤','=','≥','>','≠','≡','≢','∊','⍷','∪','∩','~','∨','∧','⍱','⍲','⍴',',','⍪','⌽','⊖','⍉','↑','↓','⊂','⊃','⌷','⍋','⍒','⊤','⊥','⍺','⍕','⍎','⊣','⊢','▯','⍞','/','\\','⍀','⌿','∘','¨','[',']','⍬','⋄','∇','⍫','(',')','←', '{', '}', '⍵', '-'].contains(&char)
+	vec!['+','−','×','÷','⌈','⌊','∣','|','⍳','?','⋆','*','⍟','○','!','⌹','<','≤','=','≥','>','≠','≡','≢','∊','⍷','∪','∩','~','∨','∧','⍱','⍲','⍴',',','⍪','⌽','⊖','⍉','↑','↓','⊂','⊃','⌷','⍋','⍒','⊤','⊥','⍺','⍕','⍎','⊣','⊢','▯','⍞','/','\\','⍀','⌿','∘','¨','[',']','⍬','⋄','∇','⍫','(',')','←', '{', '}', '⍵', '-', ':'].contains(&char)
 }
 
-fn primitive_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, String> {
+fn primitive_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, AplError> {
+	let (row, col) = char_reader.position();
 	let opening_character = char_reader.current_char.unwrap();
 	if opening_character == '∘' {
-		let backtrack = char_reader.create_backtrack();
+		//∘ is already read, so it stands alone unless a . follows it
 		char_reader.read_and_stash_char();
 		match char_reader.current_char {
 			Some('.') => {
 				char_reader.read_and_stash_char();
 				Ok(Box::new(Token::Primitive(TokenData {
 					string: "∘.".to_string(),
-					row: 0,
-					col: 0
+					row,
+					col
 				})))
 			},
 			_ => {
-				char_reader.backtrack(&backtrack);
 				Ok(Box::new(Token::Primitive(TokenData {
 					string: "∘".to_string(),
-					row: 0,
-					col: 0
+					row,
+					col
 				})))
 			}
 		}
@@ -371,8 +404,8 @@ fn primitive_tokenizer(char_reader: &mut CharReader) -> Result<Box<Token>, Strin
 		char_reader.read_and_stash_char();
 		Ok(Box::new(Token::Primitive(TokenData {
 			string: opening_character.to_string(),
-			row: 0,
-			col: 0
+			row,
+			col
 		})))
 	}
 }
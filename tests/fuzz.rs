@@ -0,0 +1,253 @@
+//Feeds the evaluator random APL and checks that it never panics, whatever the input. One
+//test throws snippets of tokens together, most of which don't parse; another builds
+//well-formed expressions from a pool of awkward values so that the primitives themselves
+//are exercised; and another nests expressions and recurses deeply, on a small stack as well
+//as a large one, as running out of stack aborts rather than panics. The runs are
+//deterministic; set FUZZ_SEED or FUZZ_ITERATIONS to explore further.
+use std::{
+	env,
+	panic,
+	thread,
+};
+use rust_apl::{
+	error::AplError,
+	eval::{
+		environment::{
+			DEFAULT_STACK_SIZE,
+			EVALUATOR_STACK_SIZE,
+		},
+		eval::{
+			Evaluator,
+			Printable,
+		},
+	},
+};
+
+const FRAGMENTS: &[&str] = &[
+	"0", "1", "2", "3", "7", "10", "¯1", "¯", ".", "2.5", "1J2", "J", "0.", " ", " ", " ",
+	"+", "-", "×", "÷", "|", "⌈", "⌊", "⋆", "*", "⍟", "○", "!", "⍴", ",", "⍪", "↑", "↓", "⌽", "⊖", "⍉", "⍋", "⍒", "⍳", "⊂", "⊃", "≢",
+	"/", "⌿", "\\", "⍀", "¨", "∘.", ".", "∘",
+	"(", ")", "(", ")", "{", "}", "⍺", "⍵", "∇", ":", "⋄", "←", "⍬",
+	"<", "≤", "=", "≥", ">", "≠", "⎕CT", "⎕CX",
+	"∧", "∨", "⍲", "⍱", "~",
+	"x", "y", "f", "⎕IO", "⎕XX", "'", "'ab'", "'c'", "[", "]", "\n", "⍝", "é", "∊", "?",
+];
+
+//Arguments for the well-formed expressions: scalars of each type, empties, nested arrays,
+//enclosed scalars and arrays of rank two and more. Any that are built by functions are in
+//parentheses, so they stay whole wherever they are put. Numbers are kept small so that no
+//expression builds an array too big to finish quickly.
+const VALUES: &[&str] = &[
+	"0", "1", "3", "¯2", "2.5", "1J2", "'a'",
+	"⍬", "''", "(0⍴⊂1 2)", "(0 2⍴0)", "(2 0⍴'a')", "(⍳0)",
+	"1 2 3", "0 1 1 0", "'abc'", "1.5 ¯2 0", "2J1 3",
+	"(2 3⍴⍳6)", "(2 2⍴'abcd')", "(3 1⍴1 0 1)", "(2 2 2⍴⍳8)", "(1 1 1 1⍴5)",
+	"(⊂1 2)", "(⊂'ab')", "(⊂⊂3)", "(⊂⍬)", "(⊂2 2⍴⍳4)",
+	"(1 2)(3 4)", "((⊂1 2)(⊂3))", "(1 (2 3) 'a')", "(2 2⍴(1 2)(3)(⍬)('ab'))",
+];
+
+const MONADIC: &[&str] = &[
+	"+", "-", "×", "÷", "|", "⌈", "⌊", "*", "⍟", "○", "!", "~", "?",
+	"⍴", ",", "⍪", "↑", "↓", "⌽", "⊖", "⍉", "⍋", "⍒", "⍳", "⊂", "⊃", "≢",
+	"+/", "×⌿", "⌈/", "∧\\", "+⍀", ",/", "⌽[1]", ",[0.5]", "↑[1]",
+	"⊂¨", "≢¨", "⍴¨", "{⍵}", "{⍵,⍵}",
+];
+
+const DYADIC: &[&str] = &[
+	"+", "-", "×", "÷", "|", "⌈", "⌊", "*", "⍟", "○", "!",
+	"<", "≤", "=", "≥", ">", "≠", "∧", "∨", "⍲", "⍱", "~", "?",
+	"⍴", ",", "⍪", "↑", "↓", "⌽", "⊖", "⍉", "⍋", "⍒", "⍳",
+	",[1]", ",[0.5]", "⍪[1]", "↑[1]", "⌽[1]", "+/", "∘.+", "∘.,", "+.×", "∧.=",
+	",¨", "⍴¨", "↑¨", "+¨", "{⍺,⍵}", "{⍺⍴⍵}",
+];
+
+//Ways to put an expression inside another, each adding a level of nesting
+const NESTINGS: &[&str] = &[
+	"(_)", "-_", "1+_", "⊂_", "{_} 1", "{⍵+_} 2", "2 {⍺,_} 3", "(_),1", "+/_",
+];
+
+//Recursive dfns, called with a depth to recurse to
+const RECURSIONS: &[&str] = &[
+	"f←{⍵=0:0 ⋄ 1+∇ ⍵-1} ⋄ f ",
+	"f←{⍵=0:⍬ ⋄ ⍵,∇ ⍵-1} ⋄ ⍴f 500⌊",
+	"f←{⍵≤1:⍵ ⋄ (∇ ⍵-1)+∇ ⍵-2} ⋄ f 10⌊",
+	"even←{⍵=0:1 ⋄ odd ⍵-1} ⋄ odd←{⍵=0:0 ⋄ even ⍵-1} ⋄ even ",
+	"f←{∇ ⍵} ⋄ f ",
+	"f←{(∇ ⍵)+1} ⋄ f ",
+];
+
+//Inputs that once panicked, named for the fault they found
+const REGRESSIONS: &[(&str, &str)] = &[
+	("catenate an enclosed scalar to a matrix", "(⊂1 2),2 2⍴⍳4"),
+	("catenate an enclosed scalar under a matrix", "(2 2⍴⍳4)⍪⊂1 2"),
+	("catenate an enclosed scalar under an empty matrix", "(0 2⍴0)⍪⊂1 2"),
+	("catenate an enclosed scalar along an axis", "(⊂1 2),[1]2 2 2⍴⍳8"),
+	("catenate enclosed scalars with each", "((⊂1 2)(⊂3)),¨(2 2⍴⍳4)(1 2)"),
+	("laminate an enclosed scalar", "(⊂1 2),[0.5]2 2⍴⍳4"),
+	("grade numbers mixed with characters", "⍋(⍳20),'ab',(⌽⍳20),'cd',(⍳20)"),
+];
+
+//xorshift64*, so the same seed always gives the same inputs
+struct Rng(u64);
+
+impl Rng {
+	fn next(&mut self) -> u64 {
+		self.0 ^= self.0 >> 12;
+		self.0 ^= self.0 << 25;
+		self.0 ^= self.0 >> 27;
+		self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+	}
+
+	fn below(&mut self, bound: usize) -> usize {
+		(self.next() % bound as u64) as usize
+	}
+
+	fn choose<'a>(&mut self, list: &[&'a str]) -> &'a str {
+		list[self.below(list.len())]
+	}
+}
+
+fn snippet(rng: &mut Rng) -> String {
+	let length = 1 + rng.below(20);
+	(0..length).map(|_| rng.choose(FRAGMENTS)).collect()
+}
+
+//An argument: a value from the pool, or once in a while a parenthesised expression of
+//its own while there are functions left to spend
+fn argument(rng: &mut Rng, functions: usize) -> String {
+	if functions > 0 && rng.below(3) == 0 {
+		format!("({})", expression(rng, functions))
+	} else {
+		rng.choose(VALUES).to_string()
+	}
+}
+
+//Either f B or A f B, using at most the given number of functions in all
+fn expression(rng: &mut Rng, functions: usize) -> String {
+	if rng.below(2) == 0 {
+		format!("{} {}", rng.choose(MONADIC), argument(rng, functions - 1))
+	} else {
+		let left_functions = rng.below(functions);
+		let left = argument(rng, left_functions);
+		let function = rng.choose(DYADIC);
+		format!("{} {} {}", left, function, argument(rng, functions - 1 - left_functions))
+	}
+}
+
+//An expression nested some levels deep, sometimes past what the parser allows
+fn nested(rng: &mut Rng) -> String {
+	let bound = [10, 100, 1000][rng.below(3)];
+	let depth = rng.below(bound);
+	let mut expression = rng.choose(VALUES).to_string();
+	for _ in 0..depth {
+		expression = rng.choose(NESTINGS).replace('_', &expression);
+	}
+	expression
+}
+
+//A recursion some calls deep, sometimes endless
+fn recursion(rng: &mut Rng) -> String {
+	let depth = [10, 100, 1000, 3000][rng.below(4)];
+	format!("{}{}", rng.choose(RECURSIONS), rng.below(depth))
+}
+
+fn setting(name: &str, default: u64) -> u64 {
+	env::var(name).ok().and_then(|value| value.parse().ok()).unwrap_or(default)
+}
+
+//Evaluates each input on a thread with the given stack, and gives back those that panicked
+//along with any that the check rejects. An input that overflows the stack aborts the whole
+//run instead, which still fails the test.
+fn run<F>(inputs: Vec<String>, stack_size: usize, check: F) -> Vec<String> where F: Fn(&AplError) -> bool + Send + 'static {
+	//Silence the default hook so only the failing inputs are reported
+	panic::set_hook(Box::new(|_| {}));
+	let failures = thread::Builder::new()
+		.stack_size(stack_size)
+		.spawn(move || {
+			let evaluator_for = || Evaluator::with_stack_size(stack_size);
+			let mut evaluator = evaluator_for();
+			let mut failures: Vec<String> = vec![];
+			for (iteration, input) in inputs.into_iter().enumerate() {
+				//Names defined by earlier inputs carry over for a while, then start afresh
+				if iteration % 50 == 0 {
+					evaluator = evaluator_for();
+				}
+				let outcome = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+					match evaluator.eval(input.clone()) {
+						Ok(Some(value)) => {
+							value.to_string();
+							true
+						},
+						Ok(None) => true,
+						Err(error) => check(&error)
+					}
+				}));
+				if !matches!(outcome, Ok(true)) {
+					failures.push(input);
+					evaluator = evaluator_for();
+				}
+			}
+			failures
+		})
+		.unwrap()
+		.join()
+		.unwrap();
+	let _ = panic::take_hook();
+	failures
+}
+
+#[test]
+fn eval_never_panics() {
+	let seed = setting("FUZZ_SEED", 0x5EED_1234_ABCD_0001);
+	let iterations = setting("FUZZ_ITERATIONS", 20_000);
+
+	let mut rng = Rng(seed);
+	let inputs: Vec<String> = (0..iterations).map(|_| snippet(&mut rng)).collect();
+	let failures = run(inputs, EVALUATOR_STACK_SIZE, |_| true);
+
+	assert!(failures.is_empty(), "{} inputs panicked, including {:?}", failures.len(), &failures[..failures.len().min(10)]);
+}
+
+#[test]
+fn expressions_never_panic() {
+	let seed = setting("FUZZ_SEED", 0x5EED_1234_ABCD_0001);
+	let iterations = setting("FUZZ_ITERATIONS", 20_000);
+
+	let mut rng = Rng(seed);
+	let inputs: Vec<String> = (0..iterations).map(|_| {
+		let functions = 1 + rng.below(2);
+		expression(&mut rng, functions)
+	}).collect();
+	//Every expression is well formed, so the evaluator may refuse one but not fail to parse it
+	for stack_size in [DEFAULT_STACK_SIZE, EVALUATOR_STACK_SIZE] {
+		let failures = run(inputs.clone(), stack_size, |error| !matches!(error, AplError::Syntax(_, _)));
+		assert!(failures.is_empty(), "{} expressions panicked or failed to parse, including {:?}", failures.len(), &failures[..failures.len().min(10)]);
+	}
+}
+
+#[test]
+fn nesting_never_overflows() {
+	let seed = setting("FUZZ_SEED", 0x5EED_1234_ABCD_0001);
+	let iterations = setting("FUZZ_ITERATIONS", 20_000) / 40;
+
+	let mut rng = Rng(seed);
+	let inputs: Vec<String> = (0..iterations).map(|_| {
+		if rng.below(5) == 0 { recursion(&mut rng) } else { nested(&mut rng) }
+	}).collect();
+	for stack_size in [DEFAULT_STACK_SIZE, EVALUATOR_STACK_SIZE] {
+		let failures = run(inputs.clone(), stack_size, |error| !matches!(error, AplError::Syntax(_, _)));
+		assert!(failures.is_empty(), "{} inputs panicked or failed to parse, including {:?}", failures.len(), &failures[..failures.len().min(10)]);
+	}
+}
+
+#[test]
+fn regressions_never_panic() {
+	let inputs: Vec<String> = REGRESSIONS.iter().map(|(_, input)| input.to_string()).collect();
+	let failures = run(inputs, DEFAULT_STACK_SIZE, |_| true);
+	let names: Vec<&str> = REGRESSIONS.iter()
+		.filter(|(_, input)| failures.iter().any(|failure| failure == input))
+		.map(|(name, _)| *name)
+		.collect();
+
+	assert!(names.is_empty(), "regressions panicked: {:?}", names);
+}